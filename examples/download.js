@@ -0,0 +1,19 @@
+const { ensureLibrary, getVersion, globalInit } = require("..")
+const path = require("path")
+
+async function main() {
+  // 首次运行下载到 libs/<version>/<平台>，之后直接使用缓存
+  const lib = await ensureLibrary({
+    cacheDir: path.join(process.cwd(), "libs"),
+    // 内网环境可指向镜像地址或存放发布包的本地目录，目录结构为 <mirror>/<version>/<文件名> 与 SHA256SUMS
+    mirror: process.env.CURL_IMPERSONATE_MIRROR,
+    // 发布没有附带 SHA256SUMS 时直接给出发布包的 SHA-256
+    sha256: process.env.CURL_IMPERSONATE_SHA256,
+  })
+  console.log(lib.downloaded ? "downloaded" : "cached", lib.path)
+
+  globalInit(3)
+  console.log(getVersion())
+}
+
+main().catch(console.error)
@@ -0,0 +1,25 @@
+const { setLibPath, Curl, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // 配置一次模板，之后按需复制
+  const template = new Curl()
+  template.impersonate("chrome136", true)
+  template.setOption(CurlOpt.Timeout, 30)
+  template.setHeadersRaw(["X-Client: crawler"])
+
+  const ids = [1, 2, 3]
+  await Promise.all(ids.map(async (id) => {
+    const curl = template.duplicate()
+    curl.setOption(CurlOpt.Url, `https://httpbin.org/anything/${id}`)
+    await curl.perform()
+    console.log(id, curl.status())
+    curl.close()
+  }))
+  template.close()
+}
+
+main()
@@ -0,0 +1,38 @@
+const { setLibPath, Curl, CurlMulti, CurlOpt, CurlError, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // socket 与 timer 由 CurlMulti 内部注册到 Node 事件循环，无需 JS 驱动
+  const multi = new CurlMulti()
+  const urls = [
+    "https://httpbin.org/get",
+    "https://httpbin.org/delay/1",
+    "https://httpbin.org/status/404",
+  ]
+  try {
+    await Promise.all(urls.map(async (url) => {
+      const curl = new Curl()
+      curl.setOption(CurlOpt.Url, url)
+      curl.impersonate("chrome136", true)
+      await multi.fetch(curl)
+      console.log(url, curl.status(), curl.getRespBody().length)
+      curl.close()
+    }))
+
+    // 失败时 Error.code 为 CurlError
+    const bad = new Curl()
+    bad.setOption(CurlOpt.Url, "http://127.0.0.1:1/")
+    await multi.fetch(bad).catch((e) => console.log("传输失败:", e.code === CurlError.CouldntConnect, e.message))
+    bad.close()
+  } finally {
+    multi.close()
+  }
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
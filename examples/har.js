@@ -0,0 +1,33 @@
+const { setLibPath, globalInit, Curl, CurlMulti, CurlOpt, HarRecorder } = require("..")
+const fs = require("fs")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // 正文超过 64KB 的部分不写入 HAR
+  const recorder = new HarRecorder({ maxBodySize: 64 * 1024 })
+  const multi = new CurlMulti()
+  multi.setHarRecorder(recorder)
+
+  const urls = ["https://tls.peet.ws/api/all", "https://httpbin.org/cookies/set?session=1"]
+  const curls = urls.map((url) => {
+    const curl = new Curl()
+    curl.setOption(CurlOpt.Url, url)
+    curl.impersonate("chrome136", true)
+    return curl
+  })
+  try {
+    await Promise.allSettled(curls.map((curl) => multi.fetch(curl)))
+  } finally {
+    curls.forEach((curl) => curl.close())
+    multi.close()
+  }
+
+  // 可直接导入 Chrome DevTools 的 Network 面板对比
+  fs.writeFileSync("requests.har", JSON.stringify(recorder, null, 2))
+  console.log(`${recorder.length} entries written to requests.har`)
+}
+
+main().catch(console.error)
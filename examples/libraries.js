@@ -0,0 +1,38 @@
+const { setLibPath, Curl, CurlLibrary, CurlMulti, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function request(library, url) {
+  // 不传 library 时使用 setLibPath 指定的全局库
+  const multi = new CurlMulti(library)
+  const curl = new Curl(library)
+  try {
+    curl.setOption(CurlOpt.Url, url)
+    curl.impersonate("chrome136", true)
+    await multi.fetch(curl)
+    return curl.getRespBody().toString()
+  } finally {
+    curl.close()
+    multi.close()
+  }
+}
+
+async function main() {
+  // 同一进程中对比两个 libcurl 构建
+  const builds = [
+    CurlLibrary.global(),
+    new CurlLibrary(path.join(process.cwd(), `/libs/x86_64-win32-next/bin/libcurl.dll`)),
+  ]
+  for (const library of builds) {
+    const info = library.getVersionInfo()
+    console.log(library.path, info.version, info.sslVersion, info.featureNames.includes("HTTP3"))
+    console.log(await request(library, "https://tls.browserleaks.com/json"))
+  }
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
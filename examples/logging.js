@@ -0,0 +1,35 @@
+const { setLibPath, globalInit, Curl, CurlInfo, CurlMulti, CurlOpt, LogLevel, enableLogging, setLogLevel, setLogSink, setModuleLogLevel } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+const LEVELS = ["DEBUG", "INFO", "WARN", "ERROR"]
+
+enableLogging(true)
+setLogLevel(LogLevel.Info)
+// CurlMulti 的日志较多，只保留警告
+setModuleLogLevel("CurlMulti", LogLevel.Warn)
+setLogSink((err, record) => {
+  if (err) return
+  const time = new Date(record.timestamp).toISOString()
+  console.log(`${time} ${LEVELS[record.level]} [${record.module}]${record.handle ? ` ${record.handle}` : ""} ${record.message}`)
+})
+
+async function main() {
+  const multi = new CurlMulti()
+  const curl = new Curl()
+  try {
+    curl.setOption(CurlOpt.Url, "https://tls.peet.ws/api/all")
+    curl.impersonate("chrome136", true)
+    await multi.fetch(curl)
+    console.log(curl.getInfoNumber(CurlInfo.ResponseCode))
+  } finally {
+    curl.close()
+    multi.close()
+  }
+  // 恢复输出到 stdout
+  setLogSink(null)
+}
+
+main().catch(console.error)
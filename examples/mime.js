@@ -0,0 +1,37 @@
+const { setLibPath, Curl, CurlMime, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  const curl = new Curl()
+  curl.setOption(CurlOpt.Url, "https://httpbin.org/post")
+  curl.impersonate("chrome136", true)
+
+  const form = new CurlMime(curl)
+  form.addPart({ name: "text", data: "hello" })
+  form.addPart({ name: "buffer", data: Buffer.from([1, 2, 3]), filename: "data.bin", contentType: "application/octet-stream" })
+  form.addPart({ name: "file", file: __filename, encoder: "base64" })
+  form.addPart({ name: "meta", data: "{}", contentType: "application/json", headers: ["X-Part: 1"] })
+
+  // 嵌套 multipart/mixed
+  const mixed = new CurlMime(curl)
+  mixed.addPart({ data: "part a", contentType: "text/plain" })
+  mixed.addPart({ data: "part b", encoder: "quoted-printable" })
+  form.addSubparts(mixed, { name: "mixed" })
+
+  // 流式 part，由 JS 分块写入
+  const index = form.addStreamPart({ name: "stream", filename: "stream.txt" })
+  curl.setMime(form)
+
+  const task = curl.perform()
+  for (let i = 0; i < 3; i++) form.writePart(index, Buffer.from(`chunk ${i}\n`))
+  form.endPart(index)
+  await task
+
+  console.log(curl.getRespBody().toString())
+  curl.close()
+}
+
+main()
@@ -0,0 +1,38 @@
+const { setLibPath, Curl, CurlMulti, CurlOpt, CurlHttpVersion, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  const multi = new CurlMulti()
+  // 只接受样式表的推送，其余推送会被取消
+  multi.setPushHandler(
+    (err, promise) => {
+      console.log("PUSH_PROMISE:", promise.method, promise.authority, promise.path, promise.headers)
+      return Boolean(promise.path && promise.path.endsWith(".css"))
+    },
+    (err, pushed) => {
+      console.log("推送完成:", pushed.promise.path, pushed.result, pushed.curl.getRespBody().length)
+      pushed.curl.close()
+    },
+  )
+
+  const curl = new Curl()
+  curl.setOption(CurlOpt.Url, "https://localhost:8443/")
+  curl.setOption(CurlOpt.HttpVersion, CurlHttpVersion.V2_0)
+  curl.setOption(CurlOpt.SslVerifyPeer, 0)
+  curl.setOption(CurlOpt.SslVerifyHost, 0)
+  try {
+    await multi.fetch(curl)
+    console.log("主请求:", curl.status(), curl.getRespBody().length)
+  } finally {
+    curl.close()
+    multi.close()
+  }
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
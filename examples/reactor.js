@@ -0,0 +1,31 @@
+const { setLibPath, Curl, CurlMulti, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // multi 独占一个后台线程，不占用 libuv 线程池
+  const multi = new CurlMulti()
+  multi.startReactor()
+  try {
+    const curls = ["https://httpbin.org/get", "https://httpbin.org/delay/1"].map((url) => {
+      const curl = new Curl()
+      curl.setOption(CurlOpt.Url, url)
+      curl.impersonate("chrome136", true)
+      return curl
+    })
+    const done = await Promise.all(curls.map((curl) => multi.addTransfer(curl)))
+    for (const curl of done) {
+      console.log(curl.status(), curl.getRespBody().length)
+      curl.close()
+    }
+  } finally {
+    multi.close()
+  }
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
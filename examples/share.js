@@ -0,0 +1,31 @@
+const { setLibPath, Curl, CurlShare, CurlShareLock, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // 多个 Curl 共享 cookie、DNS、TLS 会话与连接缓存
+  const share = new CurlShare()
+  share.share(CurlShareLock.Cookie)
+  share.share(CurlShareLock.Dns)
+  share.share(CurlShareLock.SslSession)
+  share.share(CurlShareLock.Connect)
+
+  const urls = [
+    "https://httpbin.org/cookies/set?session=abc",
+    "https://httpbin.org/cookies",
+    "https://httpbin.org/cookies",
+  ]
+  await Promise.all(urls.map(async (url) => {
+    const curl = new Curl()
+    curl.setOption(CurlOpt.Url, url)
+    curl.impersonate("chrome136", true)
+    curl.setShare(share)
+    await curl.perform()
+    console.log(url, curl.status(), curl.getRespBody().toString())
+    curl.close()
+  }))
+}
+
+main()
@@ -0,0 +1,26 @@
+const { setLibPath, globalInit, Curl, CurlOpt } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+const curl = new Curl()
+curl.setOption(CurlOpt.Url, "https://www.google.com")
+curl.setOption(CurlOpt.FollowLocation, true)
+curl.setOption(CurlOpt.AcceptEncoding, "")
+curl.impersonate("chrome136", true)
+
+await curl.perform()
+
+const stats = curl.getStats()
+// 时间为微秒级的时间点，相减得到各阶段耗时
+const ms = (us) => ((us ?? 0) / 1000).toFixed(2) + "ms"
+console.log("DNS:", ms(stats.nameLookupTime))
+console.log("TCP:", ms(stats.connectTime - stats.nameLookupTime))
+console.log("TLS:", ms(stats.appConnectTime - stats.connectTime))
+console.log("TTFB:", ms(stats.startTransferTime))
+console.log("Total:", ms(stats.totalTime))
+console.log(`HTTP/${stats.httpVersion} ${stats.responseCode} from ${stats.primaryIp}:${stats.primaryPort}`)
+console.log(`${stats.sizeDownload} bytes at ${stats.speedDownload} B/s, ${stats.redirectCount} redirects`)
+
+curl.close()
@@ -0,0 +1,36 @@
+const { setLibPath, Curl, CurlOpt, globalInit } = require("..")
+const { Readable } = require("stream")
+const { finished } = require("stream/promises")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  const curl = new Curl()
+  curl.setOption(CurlOpt.Url, "https://httpbin.org/stream-bytes/1048576")
+  curl.impersonate("chrome136", true)
+
+  // 包装为 Readable，数据被消费后再确认，形成背压；传输结束时 chunk 为 null，结束 Readable
+  const body = new Readable({ read() { } })
+  curl.setBodyStream((err, chunk) => {
+    if (err) return body.destroy(err)
+    body.push(chunk)
+  }, 64 * 1024)
+
+  let total = 0
+  body.on("data", (chunk) => {
+    total += chunk.length
+    curl.consumeBody(chunk.length)
+  })
+
+  await curl.perform()
+  await finished(body)
+  console.log("接收字节数:", total)
+  curl.close()
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
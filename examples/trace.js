@@ -0,0 +1,29 @@
+const { setLibPath, globalInit, globalTrace, Curl, CurlInfoType, CurlOpt } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+// 额外输出 TLS 与 HTTP/2 组件的详细信息
+globalTrace("tls,http/2")
+
+const NAMES = ["TEXT", "HEADER_IN", "HEADER_OUT", "DATA_IN", "DATA_OUT", "SSL_DATA_IN", "SSL_DATA_OUT"]
+
+async function main() {
+  const curl = new Curl()
+  try {
+    curl.setOption(CurlOpt.Url, "https://tls.peet.ws/api/all")
+    curl.impersonate("chrome136", true)
+    // 不传回调时收集到内存，TLS 加密数据量较大，这里只保留明文部分
+    curl.enableTrace(null, {
+      types: [CurlInfoType.Text, CurlInfoType.HeaderOut, CurlInfoType.HeaderIn, CurlInfoType.DataOut],
+    })
+    await curl.perform()
+    for (const entry of curl.getTrace()) {
+      process.stdout.write(`#${entry.connId ?? "-"} ${NAMES[entry.type]} ${entry.data.toString()}`)
+    }
+  } finally {
+    curl.close()
+  }
+}
+
+main().catch(console.error)
@@ -0,0 +1,45 @@
+const { setLibPath, Curl, CurlOpt, globalInit } = require("..")
+const EventEmitter = require("events")
+const { once } = EventEmitter
+const fs = require("fs")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+// 将 Readable / async iterator 写入流式请求体，缓冲满时等待 drain
+async function pipeUpload(curl, source, drain) {
+  for await (const chunk of source) {
+    if (!curl.writeUpload(Buffer.from(chunk))) {
+      await once(drain, "drain")
+    }
+  }
+  curl.endUpload()
+}
+
+async function main() {
+  const file = path.join(__dirname, "post.js")
+
+  // 大小未知，使用 chunked 编码
+  const curl = new Curl()
+  curl.setOption(CurlOpt.Url, "https://httpbin.org/put")
+  const drain = new EventEmitter()
+  curl.setUploadStream(null, () => drain.emit("drain"), 16 * 1024)
+  const request = curl.perform()
+  await Promise.all([pipeUpload(curl, fs.createReadStream(file), drain), request])
+  console.log(curl.status(), curl.getRespBody().toString("utf8"))
+  curl.close()
+
+  // 直接从文件上传，自动设置 InFileSizeLarge
+  const curl2 = new Curl()
+  curl2.setOption(CurlOpt.Url, "https://httpbin.org/put")
+  curl2.setUploadFile(file)
+  await curl2.perform()
+  console.log(curl2.status(), curl2.getRespBody().toString("utf8"))
+  curl2.close()
+}
+
+main().catch((e) => {
+  console.error("示例运行失败:", e)
+  process.exitCode = 1
+})
@@ -0,0 +1,31 @@
+const { setLibPath, Curl, CurlUrl, CurlUPart, CurlUFlag, CurlOpt, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+async function main() {
+  // 按 libcurl 规则解析，而非 WHATWG URL
+  const url = new CurlUrl("HTTPS://Example.com:443/a/../b?x=1#top")
+  console.log("scheme:", url.get(CurlUPart.Scheme))
+  console.log("host:", url.get(CurlUPart.Host))
+  console.log("port:", url.get(CurlUPart.Port, CurlUFlag.DefaultPort))
+  console.log("path:", url.get(CurlUPart.Path))
+
+  url.set(CurlUPart.Url, "https://httpbin.org/get")
+  url.appendQuery("q", "hello world")
+  url.set(CurlUPart.Fragment, null)
+  console.log("href:", url.href())
+
+  const idn = new CurlUrl("https://räksmörgås.se/")
+  console.log("punycode:", idn.get(CurlUPart.Host, CurlUFlag.Punycode))
+
+  const curl = new Curl()
+  curl.impersonate("chrome136", true)
+  curl.setCurlUrl(url)
+  await curl.perform()
+  console.log(curl.status(), curl.getRespBody().toString())
+  curl.close()
+}
+
+main()
@@ -0,0 +1,41 @@
+const { setLibPath, Curl, CurlOpt, CurlWebSocket, CurlWsFlag, globalInit } = require("..")
+const path = require("path")
+
+setLibPath(path.join(process.cwd(), `/libs/x86_64-win32/bin/libcurl.dll`))
+globalInit(3)
+
+// 包装为异步迭代器，连接关闭时结束
+async function* messages(ws) {
+  while (true) {
+    const msg = await ws.recv()
+    if (!msg) return
+    yield msg
+  }
+}
+
+async function main() {
+  const curl = new Curl()
+  curl.setOption(CurlOpt.Url, "wss://echo.websocket.org")
+  curl.impersonate("chrome136", true)
+
+  const ws = new CurlWebSocket(curl)
+  await ws.connect()
+  await ws.send("hello")
+  await ws.send(Buffer.from([1, 2, 3]))
+
+  let count = 0
+  for await (const msg of messages(ws)) {
+    if (msg.flags & CurlWsFlag.Close) {
+      console.log("对端关闭:", msg.closeCode, msg.closeReason)
+      continue
+    }
+    const text = msg.flags & CurlWsFlag.Text
+    console.log("收到:", text ? msg.data.toString() : msg.data)
+    if (++count >= 3) break
+  }
+
+  await ws.close(1000, "bye")
+  curl.close()
+}
+
+main()
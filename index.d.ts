@@ -2,17 +2,40 @@
 /* eslint-disable */
 export declare class Curl {
   closed: boolean
-  constructor()
+  /** library 默认为全局库 */
+  constructor(library?: CurlLibrary | undefined | null)
+  /**
+   * 复制当前 Curl 的全部选项，得到独立的新实例
+   *
+   * 请求头链表与请求体会深拷贝；流式响应体、流式请求体与进度回调不会被复制
+   */
+  duplicate(): Curl
   /** 初始化数据回调 */
   init(): void
   setHeadersRaw(headers: Array<string>): void
-  setOption(option: CurlOpt, value: string | number | boolean): void
+  /** 按选项类型校验并设置，类型不符时抛出 InvalidArg */
+  setOption(option: CurlOpt, value: string | number | boolean | Array<string> | Buffer): void
+  /** 按 libcurl 选项名设置，如 "TIMEOUT_MS"，可带 CURLOPT_ 前缀，不区分大小写 */
+  setOptionByName(name: string, value: string | number | boolean | Array<string> | Buffer): void
   setBody(value: string | Buffer): void
   setOptStrList(option: CurlOpt, arrays: Array<string>): void
-  /** 获取响应码 */
+  /**
+   * 获取数值信息，LONG、DOUBLE、OFF_T、SOCKET 类型均按其 C 类型读取
+   *
+   * 无效的 socket 返回 -1
+   */
   getInfoNumber(option: CurlInfo): number
-  /** 获取字符串信息 */
-  getInfoString(option: CurlInfo): string
+  /** 获取字符串信息，未设置时为 null */
+  getInfoString(option: CurlInfo): string | null
+  /**
+   * 按信息的类型位读取并转换为对应的 JS 类型
+   *
+   * STRING 为字符串或 null，LONG、DOUBLE、SOCKET 为数字，OFF_T 超出安全整数范围时为 BigInt，
+   * SLIST 为字符串数组，CertInfo 为每个证书的 "名称:值" 数组
+   */
+  getInfoValue(option: CurlInfo): string | number | bigint | Array<string> | Array<Array<string>> | null
+  /** 获取传输统计，各项信息按其 C 类型读取 */
+  getStats(): CurlStats
   /** 模拟浏览器 */
   impersonate(target: string, defaultHeaders?: boolean | undefined | null): void
   /** 获取错误信息字符串 */
@@ -32,7 +55,80 @@ export declare class Curl {
   getRespHeaders(): Buffer
   /** 获取响应体数据 */
   getRespBody(): Buffer
-  /** 获取信息数组 */
+  /** 通过 CURLOPT_MIMEPOST 发送 multipart 表单，表单随 Curl 一起释放 */
+  setMime(mime: CurlMime): void
+  /** 通过 CURLOPT_CURLU 使用 CurlUrl 作为请求地址，优先于 CurlOpt.Url */
+  setCurlUrl(url: CurlUrl): void
+  /** 停止使用 CurlUrl */
+  clearCurlUrl(): void
+  /** 使用共享句柄，共享句柄在 Curl 释放前不会被清理 */
+  setShare(share: CurlShare): void
+  /** 停止使用共享句柄 */
+  clearShare(): void
+  /** 移除 multipart 表单 */
+  clearMime(): void
+  /** 解析响应头，每次重定向、1xx 或 CONNECT 响应各为一跳，最后一项为最终响应 */
+  getResponseHops(): Array<ResponseHop>
+  /**
+   * 启用流式响应体：每个数据块通过回调投递，不再缓存到 get_resp_body
+   *
+   * 传输结束时回调收到 null，失败时收到错误，均在 perform 等 Promise 结算之前投递；
+   * 未调用 consume_body 确认的字节数超过 high_water_mark 时传输会被暂停
+   */
+  setBodyStream(callback: ((err: Error | null, arg: Buffer | null) => any), highWaterMark?: number | undefined | null): void
+  /** 关闭流式响应体，恢复为缓存模式 */
+  clearBodyStream(): void
+  /** 确认已消费的响应体字节数，必要时恢复被暂停的传输 */
+  consumeBody(bytes: number): void
+  /**
+   * 启用流式请求体，数据通过 write_upload 写入、end_upload 结束
+   *
+   * 以 POST 发送，其他方法可通过 CURLOPT_CUSTOMREQUEST 指定；size 未知时使用 chunked 编码；
+   * write_upload 返回 false 时应等待 on_drain 回调再继续写入
+   */
+  setUploadStream(size?: number | undefined | null, onDrain?: ((err: Error | null, arg: undefined) => any) | undefined | null, highWaterMark?: number | undefined | null): void
+  /** 从文件流式上传请求体 */
+  setUploadFile(path: string): void
+  /** 写入一块请求体数据，返回 false 表示缓冲已满 */
+  writeUpload(chunk: Buffer): boolean
+  /** 结束流式请求体 */
+  endUpload(): void
+  /** 关闭流式请求体 */
+  clearUploadStream(): void
+  /**
+   * 设置进度回调，回调返回 true 时中止传输（CurlError.AbortedByCallback）
+   *
+   * interval_ms 为两次回调的最小间隔，默认 100ms
+   */
+  onProgress(callback: ((err: Error | null, arg: ProgressData) => boolean | undefined | null), intervalMs?: number | undefined | null): void
+  /** 移除进度回调 */
+  clearProgress(): void
+  /**
+   * 开启线路追踪，通过 CURLOPT_DEBUGFUNCTION 记录收发的文本、头部、数据与 TLS 原始字节
+   *
+   * 传入 callback 时逐条交给 JS，否则收集到内存中，传输结束后通过 getTrace 取出；
+   * 会同时开启 CURLOPT_VERBOSE
+   */
+  enableTrace(callback?: ((err: Error | null, entry: TraceEntry) => any) | undefined | null, options?: TraceOptions | undefined | null): void
+  /** 关闭线路追踪并丢弃未取出的记录 */
+  disableTrace(): void
+  /** 取出并清空已收集的追踪记录，未开启追踪或设置了回调时为空 */
+  getTrace(): Array<TraceEntry>
+  /**
+   * 将之后每次传输记录到 HAR，传空取消
+   *
+   * 会开启 CURLOPT_DEBUGFUNCTION 以获取实际发出的请求头与请求体
+   */
+  setHarRecorder(recorder?: HarRecorder | undefined | null): void
+  /**
+   * 获取指定响应头
+   *
+   * origin 为 CurlHeaderOrigin 的按位组合，默认 Header；request 为请求序号（重定向的每一跳），默认 -1 即最后一个请求
+   */
+  getHeader(name: string, index?: number | undefined | null, origin?: number | undefined | null, request?: number | undefined | null): CurlHeaderEntry | null
+  /** 按顺序获取某个请求的全部响应头，参数含义同 get_header */
+  getHeaders(origin?: number | undefined | null, request?: number | undefined | null): Array<CurlHeaderEntry>
+  /** 获取信息数组，支持 SslEngines、CookieList */
   getInfoList(option: CurlInfo): Array<string>
   /** 设置链表 */
   setOptList(option: CurlOpt, arrays: Array<string>): void
@@ -43,14 +139,85 @@ export declare class Curl {
   status(): number
 }
 
+/**
+ * 一个已加载的 libcurl 构建，传给 Curl、CurlMulti、CurlShare、CurlUrl 的构造函数后由其使用
+ *
+ * 同一路径只加载一次；来自不同库的句柄不能混用
+ */
+export declare class CurlLibrary {
+  /** 加载指定路径的 libcurl */
+  constructor(path: string)
+  /** 全局默认库，即 setLibPath 指定的路径 */
+  static global(): CurlLibrary
+  get path(): string
+  /** 是否与全局默认库是同一个库 */
+  isGlobal(): boolean
+  /** curl_version() 字符串 */
+  getVersion(): string
+  getVersionInfo(): VersionInfo
+  getCapabilities(): Array<CurlCapability>
+  /** 该库的 curl_global_trace */
+  globalTrace(config: string): void
+  getCurlOptions(): Array<CurlOptionInfo>
+}
+
+export declare class CurlMime {
+  constructor(curl: Curl)
+  /** 添加文本、Buffer 或文件 part */
+  addPart(options: MimePartOptions): void
+  /** 添加嵌套的 multipart/mixed part，sub 此后由当前 mime 管理，不能再次挂载 */
+  addSubparts(sub: CurlMime, options?: MimePartOptions | undefined | null): void
+  /**
+   * 添加流式 part，数据通过 write_part 写入、end_part 结束，返回 part 序号
+   *
+   * size 未知时整个请求使用 chunked 编码；write_part 返回 false 时应等待 on_drain 回调
+   */
+  addStreamPart(options: MimePartOptions, size?: number | undefined | null, onDrain?: ((err: Error | null, arg: undefined) => any) | undefined | null, highWaterMark?: number | undefined | null): number
+  /** 向流式 part 写入数据，返回 false 表示缓冲已满 */
+  writePart(index: number, chunk: Buffer): boolean
+  /** 结束流式 part */
+  endPart(index: number): void
+}
+
 export declare class CurlMulti {
   closed: boolean
-  constructor()
+  /** library 默认为全局库，只能添加来自同一个库的 Curl */
+  constructor(library?: CurlLibrary | undefined | null)
   setOption(option: CurlMOpt, value: string | number | boolean): void
   setOptBuffer(option: CurlMOpt, body: Buffer): void
   setSocketCallback(callback: ((err: Error | null, arg: SocketData) => any)): void
   setTimerCallback(callback: ((err: Error | null, arg: TimerData) => any)): void
+  /**
+   * 启动后台 reactor 线程循环 multi_poll 驱动传输，完成结果通过 addTransfer 的 Promise 返回
+   *
+   * 不支持服务器推送，已设置 setPushHandler 时需先 clearPushHandler
+   */
+  startReactor(): void
+  /** 停止后台 reactor 线程，未完成的传输保留在 multi 中 */
+  stopReactor(): void
+  /**
+   * 处理 HTTP/2 服务器推送：handler 收到 PUSH_PROMISE 后返回 true 接受，否则取消该推送
+   *
+   * 已接受的推送完成后以新的 Curl 交给 onPushed
+   */
+  setPushHandler(handler: ((err: Error | null, arg: CurlPushPromise) => boolean | undefined | null), onPushed: ((err: Error | null, arg: CurlPushed) => any)): void
+  /**
+   * 将之后经 addTransfer、fetch 完成的传输记录到 HAR，传空取消
+   *
+   * Curl 自身通过 setHarRecorder 挂载了记录器时记录到其自身的记录器
+   */
+  setHarRecorder(recorder?: HarRecorder | undefined | null): void
+  /** 移除推送处理函数，之后的推送都会被拒绝 */
+  clearPushHandler(): void
   addHandle(curl: Curl): number
+  /**
+   * 添加 Curl，CURLMSG_DONE 时 Promise 以该 Curl 解决，失败时以带 code（CurlError）的 Error 拒绝
+   *
+   * 完成的句柄会自动移出 multi；手动驱动时需调用 infoRead 读取完成消息
+   */
+  addTransfer(curl: Curl): Promise<Curl>
+  /** 同 addTransfer，并由内置的 libuv 事件循环驱动，无需 JS 端调用 perform/socketAction */
+  fetch(curl: Curl): Promise<Curl>
   removeHandle(curl: Curl): number
   error(err: number): string
   perform(): number
@@ -64,6 +231,75 @@ export declare class CurlMulti {
   close(): void
 }
 
+export declare class CurlShare {
+  /** library 默认为全局库 */
+  constructor(library?: CurlLibrary | undefined | null)
+  /** 开始共享某类数据 */
+  share(data: CurlShareLock): void
+  /** 停止共享某类数据 */
+  unshare(data: CurlShareLock): void
+}
+
+export declare class CurlUrl {
+  /** 创建 URL，可选地解析 url；flags 为 CurlUFlag 的按位组合，library 默认为全局库 */
+  constructor(url?: string | undefined | null, flags?: number | undefined | null, library?: CurlLibrary | undefined | null)
+  /**
+   * 设置某一部分，value 为 null 时清除该部分
+   *
+   * 设置 Url 时相对地址会基于当前 URL 解析；Query 配合 CurlUFlag.AppendQuery 追加查询参数
+   */
+  set(part: CurlUPart, value?: string | undefined | null, flags?: number | undefined | null): void
+  /** 获取某一部分，不存在时返回 null */
+  get(part: CurlUPart, flags?: number | undefined | null): string | null
+  /** 追加一个查询参数，name=value 会按 libcurl 规则编码 */
+  appendQuery(name: string, value?: string | undefined | null): void
+  /** 完整 URL */
+  href(flags?: number | undefined | null): string
+  /** 复制为独立的 CurlUrl */
+  duplicate(): CurlUrl
+}
+
+export declare class CurlWebSocket {
+  /** 使用已配置好（URL、impersonate 等）的 Curl 创建 WebSocket */
+  constructor(curl: Curl)
+  /** 以 CONNECT_ONLY=2 完成 WebSocket 握手 */
+  connect(): Promise<unknown>
+  /** 发送消息，默认字符串为 Text、Buffer 为 Binary */
+  send(data: string | Buffer, flags?: number | undefined | null): Promise<void>
+  /** 发送 PING */
+  ping(payload?: Buffer | undefined | null): Promise<void>
+  /** 接收下一条完整消息，连接关闭后返回 null */
+  recv(): Promise<WsMessage | null>
+  /** 在后台线程持续接收消息并回调，连接关闭时以 null 结束 */
+  onMessage(callback: ((err: Error | null, arg?: WsMessage | undefined | null) => any)): void
+  /** 发起关闭握手，等待对端回应 CLOSE 帧 */
+  close(code?: number | undefined | null, reason?: string | undefined | null): Promise<void>
+  get closed(): boolean
+}
+
+/**
+ * HAR 记录器，通过 Curl.setHarRecorder 或 CurlMulti.setHarRecorder 挂载
+ *
+ * JSON.stringify(recorder) 即得到 HAR 文件内容
+ */
+export declare class HarRecorder {
+  constructor(options?: HarRecorderOptions | undefined | null)
+  /** 已记录的条目数 */
+  get length(): number
+  /** 清空已记录的条目 */
+  clear(): void
+  /** 生成 HAR 1.2 对象 */
+  getHar(): Har
+  toJSON(): Har
+}
+
+/** 已加载的 libcurl 中可选符号的可用情况 */
+export interface CurlCapability {
+  /** 符号名，如 curl_easy_impersonate */
+  symbol: string
+  available: boolean
+}
+
 export declare function curlEasyError(code: number): string
 
 export declare const enum CurlError {
@@ -171,6 +407,36 @@ export declare const enum CurlError {
   EchRequired = 101
 }
 
+export interface CurlHeaderEntry {
+  name: string
+  value: string
+  /** 同名头部的总数 */
+  amount: number
+  /** 当前头部在同名头部中的序号 */
+  index: number
+  /** 来源，见 CurlHeaderOrigin */
+  origin: number
+}
+
+export declare const enum CurlHeaderError {
+  Ok = 0,
+  BadIndex = 1,
+  Missing = 2,
+  NoHeaders = 3,
+  NoRequest = 4,
+  OutOfMemory = 5,
+  BadArgument = 6,
+  NotBuiltIn = 7
+}
+
+export declare const enum CurlHeaderOrigin {
+  Header = 1,
+  Trailer = 2,
+  Connect = 4,
+  Informational = 8,
+  Pseudo = 16
+}
+
 export declare const enum CurlHttpVersion {
   None = 0,
   V1_0 = 1,
@@ -290,6 +556,17 @@ export declare const enum CurlInfo {
   LastOne = 70
 }
 
+/** CURLOPT_DEBUGFUNCTION 回调的数据类型 */
+export declare const enum CurlInfoType {
+  Text = 0,
+  HeaderIn = 1,
+  HeaderOut = 2,
+  DataIn = 3,
+  DataOut = 4,
+  SslDataIn = 5,
+  SslDataOut = 6
+}
+
 export declare const enum CurlIpResolve {
   Whatever = 0,
   V4 = 1,
@@ -660,6 +937,72 @@ export declare const enum CurlOpt {
   TlsUseFirefoxTls13Ciphers = 1021
 }
 
+/** 已加载的 libcurl 支持的选项 */
+export interface CurlOptionInfo {
+  /** 不带 CURLOPT_ 前缀的名称，如 TIMEOUT_MS */
+  name: string
+  id: number
+  type?: CurlOptType
+  flags: number
+  /** 是否为旧名称的别名 */
+  alias: boolean
+  /** 是否已收录在 CurlOpt 枚举中 */
+  known: boolean
+}
+
+export declare const enum CurlOptType {
+  Long = 0,
+  Values = 1,
+  OffT = 2,
+  Object = 3,
+  String = 4,
+  Slist = 5,
+  CbPtr = 6,
+  Blob = 7,
+  Function = 8
+}
+
+/** 已接受的推送传输完成后交付给 JS */
+export interface CurlPushed {
+  promise: CurlPushPromise
+  /** 传输结果（CurlError），0 表示成功 */
+  result: number
+  curl: Curl
+}
+
+/** PUSH_PROMISE 携带的伪头部与头部 */
+export interface CurlPushPromise {
+  /** 推送传输的 id，与交付的 Curl.id() 一致 */
+  id: string
+  /** 发起原请求的 Curl id */
+  parentId: string
+  method?: string
+  scheme?: string
+  authority?: string
+  path?: string
+  headers: Array<ResponseHeader>
+}
+
+export declare function curlShareError(code: number): string
+
+export declare const enum CurlShareLock {
+  Share = 1,
+  Cookie = 2,
+  Dns = 3,
+  SslSession = 4,
+  Connect = 5,
+  Psl = 6,
+  Hsts = 7
+}
+
+export declare const enum CurlShOpt {
+  Share = 1,
+  Unshare = 2,
+  LockFunc = 3,
+  UnlockFunc = 4,
+  UserData = 5
+}
+
 export declare const enum CurlSslVersion {
   Default = 0,
   TlsV1 = 1,
@@ -672,32 +1015,393 @@ export declare const enum CurlSslVersion {
   MaxDefault = 65536
 }
 
+/**
+ * 传输统计快照，libcurl 不支持或尚未产生的信息为空
+ *
+ * 时间均为微秒，且是从传输开始到该阶段结束的时间点，而非各阶段耗时
+ */
+export interface CurlStats {
+  /** 在连接池队列中等待的时间（libcurl 8.6+） */
+  queueTime?: number
+  nameLookupTime?: number
+  connectTime?: number
+  /** TLS 握手完成，非 HTTPS 时为 0 */
+  appConnectTime?: number
+  preTransferTime?: number
+  /** 请求体发送完成（libcurl 8.10+） */
+  postTransferTime?: number
+  /** 收到首字节 */
+  startTransferTime?: number
+  totalTime?: number
+  /** 所有重定向耗时之和 */
+  redirectTime?: number
+  /** 下载的正文字节数（解压前） */
+  sizeDownload?: number
+  sizeUpload?: number
+  /** 收到的所有响应头字节数 */
+  headerSize?: number
+  /** 发出的所有请求字节数 */
+  requestSize?: number
+  /** Content-Length，未知时为空 */
+  contentLengthDownload?: number
+  contentLengthUpload?: number
+  /** 平均下载速度（字节/秒） */
+  speedDownload?: number
+  speedUpload?: number
+  responseCode?: number
+  redirectCount?: number
+  /** 本次传输新建的连接数 */
+  numConnects?: number
+  primaryIp?: string
+  primaryPort?: number
+  localIp?: string
+  localPort?: number
+  /** 实际使用的 HTTP 版本，如 "1.1"、"2"、"3" */
+  httpVersion?: string
+  /** 协议，如 "https" */
+  scheme?: string
+  effectiveUrl?: string
+  /** 传输 id（libcurl 8.2+） */
+  xferId?: number
+  /** 连接 id（libcurl 8.2+） */
+  connId?: number
+  /** 是否经过代理（libcurl 8.7+） */
+  usedProxy?: boolean
+}
+
+export declare const enum CurlUError {
+  Ok = 0,
+  BadHandle = 1,
+  BadPartpointer = 2,
+  MalformedInput = 3,
+  BadPortNumber = 4,
+  UnsupportedScheme = 5,
+  UrlDecode = 6,
+  OutOfMemory = 7,
+  UserNotAllowed = 8,
+  UnknownPart = 9,
+  NoScheme = 10,
+  NoUser = 11,
+  NoPassword = 12,
+  NoOptions = 13,
+  NoHost = 14,
+  NoPort = 15,
+  NoQuery = 16,
+  NoFragment = 17,
+  NoZoneId = 18,
+  BadFileUrl = 19,
+  BadFragment = 20,
+  BadHostname = 21,
+  BadIpv6 = 22,
+  BadLogin = 23,
+  BadPassword = 24,
+  BadPath = 25,
+  BadQuery = 26,
+  BadScheme = 27,
+  BadSlashes = 28,
+  BadUser = 29,
+  LackIdn = 30,
+  TooLarge = 31
+}
+
+export declare const enum CurlUFlag {
+  DefaultPort = 1,
+  NoDefaultPort = 2,
+  DefaultScheme = 4,
+  NonSupportScheme = 8,
+  PathAsIs = 16,
+  DisallowUser = 32,
+  UrlDecode = 64,
+  UrlEncode = 128,
+  AppendQuery = 256,
+  GuessScheme = 512,
+  NoAuthority = 1024,
+  AllowSpace = 2048,
+  Punycode = 4096,
+  PunyToIdn = 8192,
+  GetEmpty = 16384,
+  NoGuessScheme = 32768
+}
+
+export declare const enum CurlUPart {
+  Url = 0,
+  Scheme = 1,
+  User = 2,
+  Password = 3,
+  Options = 4,
+  Host = 5,
+  Port = 6,
+  Path = 7,
+  Query = 8,
+  Fragment = 9,
+  ZoneId = 10
+}
+
+export declare function curlUrlError(code: number): string
+
 export declare const enum CurlWsFlag {
   Text = 1,
   Binary = 2,
   Cont = 4,
   Close = 8,
   Ping = 16,
-  Offset = 32
+  Offset = 32,
+  Pong = 64
 }
 
+/** 启用日志输出 */
+export declare function enableLogging(enable: boolean): void
+
+export interface EnsuredLibrary {
+  /** 动态库路径 */
+  path: string
+  /** 是否已设为全局库路径；全局库已从其他路径加载时为 false，可改用 CurlLibrary 加载 */
+  global: boolean
+  /** 解压目录 */
+  dir: string
+  version: string
+  /** 本次是否下载，命中缓存时为 false */
+  downloaded: boolean
+}
+
+/** 确保当前平台的 libcurl-impersonate 已下载到缓存目录，并尝试设为全局库路径 */
+export declare function ensureLibrary(options?: EnsureLibraryOptions | undefined | null): Promise<EnsuredLibrary>
+
+export interface EnsureLibraryOptions {
+  /** 发布版本，如 v1.0.0；未指定时取环境变量 CURL_IMPERSONATE_VERSION，再其次为本包发布时验证过的内置版本 */
+  version?: string
+  /** 缓存目录，默认为当前目录下的 libs，解压到 <cacheDir>/<version>/<平台> */
+  cacheDir?: string
+  /** 替代 GitHub releases 的下载地址前缀，按 <mirror>/<version>/<文件名> 拼接；也可以是本地目录 */
+  mirror?: string
+  /** 直接指定发布包的 URL 或本地路径，用于离线安装 */
+  source?: string
+  /** 校验清单的 URL 或本地路径，默认为发布包同目录下的 SHA256SUMS；发布没有清单时需改用 sha256 */
+  checksums?: string
+  /** 发布包的 SHA-256，指定后不再读取校验清单 */
+  sha256?: string
+}
+
+/** 列出可选符号是否存在于已加载的 libcurl 中，缺少时相关 API 会抛出错误 */
+export declare function getCapabilities(): Array<CurlCapability>
+
+/** 按名称（可带 CURLOPT_ 前缀，不区分大小写）或 id 查询选项 */
+export declare function getCurlOption(option: string | number): CurlOptionInfo | null
+
+/** 列出已加载的 libcurl 支持的全部选项 */
+export declare function getCurlOptions(): Array<CurlOptionInfo>
+
 export declare function getDefaultDirName(): string
 
 export declare function getDefaultLibPath(base?: string | undefined | null): string
 
 export declare function getLibPath(): string | null
 
+/** 获取当前日志级别 */
+export declare function getLogLevel(): LogLevel
+
 /** 获取 libcurl 版本信息 */
 export declare function getVersion(): string
 
+/** 获取 libcurl 的结构化构建信息，用于检测 HTTP/3、ECH、brotli 等特性是否可用 */
+export declare function getVersionInfo(): VersionInfo
+
 /** 全局清理 */
 export declare function globalCleanup(): void
 
 /** 全局初始化 */
 export declare function globalInit(flags: number): number
 
+/**
+ * 开启 libcurl 组件级追踪（curl_global_trace），如 "tls,http/2"、"-all"
+ *
+ * 输出经由 CURLOPT_DEBUGFUNCTION 交付，需配合 Curl.enableTrace 使用
+ */
+export declare function globalTrace(config: string): void
+
+export interface Har {
+  log: HarLog
+}
+
+export interface HarCache {
+
+}
+
+export interface HarContent {
+  /** 解码后的大小 */
+  size: number
+  /** 压缩节省的字节数 */
+  compression?: number
+  mimeType: string
+  text?: string
+  /** 响应体不是 UTF-8 时为 base64 */
+  encoding?: string
+  comment?: string
+}
+
+export interface HarCookie {
+  name: string
+  value: string
+  path?: string
+  domain?: string
+  /** Set-Cookie 中的原始 Expires */
+  expires?: string
+  httpOnly?: boolean
+  secure?: boolean
+}
+
+export interface HarCreator {
+  name: string
+  version: string
+}
+
+export interface HarEntry {
+  startedDateTime: string
+  /** 总耗时（毫秒），即 TotalTimeT，包含重定向 */
+  time: number
+  request: HarRequest
+  response: HarResponse
+  cache: HarCache
+  timings: HarTimings
+  serverIPAddress?: string
+  /** 连接 id（CURLINFO_CONN_ID） */
+  connection?: string
+  /** 传输失败时的错误信息 */
+  comment?: string
+}
+
+export interface HarLog {
+  version: string
+  creator: HarCreator
+  entries: Array<HarEntry>
+}
+
+export interface HarNameValue {
+  name: string
+  value: string
+}
+
+export interface HarPostData {
+  mimeType: string
+  text: string
+  comment?: string
+}
+
+export interface HarRecorderOptions {
+  /** 请求体、响应体各保留的最大字节数，超出部分截断，默认 1MB；为 0 时不记录正文 */
+  maxBodySize?: number
+  creator?: HarCreator
+}
+
+export interface HarRequest {
+  method: string
+  url: string
+  httpVersion: string
+  cookies: Array<HarCookie>
+  headers: Array<HarNameValue>
+  queryString: Array<HarNameValue>
+  postData?: HarPostData
+  headersSize: number
+  bodySize: number
+}
+
+export interface HarResponse {
+  status: number
+  statusText: string
+  httpVersion: string
+  cookies: Array<HarCookie>
+  headers: Array<HarNameValue>
+  content: HarContent
+  redirectURL: string
+  headersSize: number
+  bodySize: number
+}
+
+/** 各阶段耗时（毫秒），不适用时为 -1 */
+export interface HarTimings {
+  blocked: number
+  dns: number
+  connect: number
+  send: number
+  wait: number
+  receive: number
+  ssl: number
+}
+
+/** 日志级别 */
+export declare const enum LogLevel {
+  Debug = 0,
+  Info = 1,
+  Warn = 2,
+  Error = 3
+}
+
+/** 交给 JS 日志接收函数的一条日志 */
+export interface LogRecord {
+  level: LogLevel
+  /** 产生日志的模块，如 Curl、CurlMulti */
+  module: string
+  /** 相关句柄的地址，与 Curl.id() 一致 */
+  handle?: string
+  message: string
+  /** 毫秒时间戳，可直接传给 new Date() */
+  timestamp: number
+}
+
+export interface MimePartOptions {
+  /** 表单字段名 */
+  name?: string
+  /** 内存数据，与 file 二选一 */
+  data?: string | Buffer
+  /** 文件路径，传输时由 libcurl 读取 */
+  file?: string
+  /** 远端看到的文件名，默认取 file 的文件名 */
+  filename?: string
+  contentType?: string
+  /** 额外的 part 头部，如 "Content-ID: <abc>" */
+  headers?: Array<string>
+  /** 传输编码：binary、8bit、7bit、base64、quoted-printable */
+  encoder?: string
+}
+
+export interface ProgressData {
+  dltotal: number
+  dlnow: number
+  ultotal: number
+  ulnow: number
+}
+
+export interface ResponseHeader {
+  name: string
+  value: string
+}
+
+export interface ResponseHop {
+  status: number
+  /** HTTP 版本，如 "1.1"、"2"、"3" */
+  httpVersion: string
+  /** 原因短语，HTTP/2 及以上通常为空 */
+  reason: string
+  /** 按接收顺序排列的响应头，同名头部保留多条 */
+  headers: Array<ResponseHeader>
+}
+
+/** 设置全局默认库的路径；全局库加载后不能再更换，其他构建请使用 CurlLibrary 加载 */
 export declare function setLibPath(path: string): void
 
+/** 设置日志级别 */
+export declare function setLogLevel(level: LogLevel): void
+
+/**
+ * 设置日志接收函数，传空恢复输出到 stdout/stderr
+ *
+ * 接收函数不会阻止进程退出，退出前尚未投递的日志会被丢弃
+ */
+export declare function setLogSink(sink?: ((err: Error | null, record: LogRecord) => any) | undefined | null): void
+
+/** 单独设置某个模块的日志级别，level 为空时恢复使用全局级别 */
+export declare function setModuleLogLevel(module: string, level?: LogLevel | undefined | null): void
+
 export interface SocketData {
   curlId: string
   sockfd: number
@@ -712,3 +1416,61 @@ export interface TimerData {
   multiId: string
   timeoutMs: number
 }
+
+/** 一条追踪记录 */
+export interface TraceEntry {
+  type: CurlInfoType
+  /** 原始字节，TEXT 与 HEADER_* 可直接按文本解码 */
+  data: Buffer
+  /** 毫秒时间戳 */
+  timestamp: number
+  /** 连接 id（CURLINFO_CONN_ID），libcurl 不支持或尚未建立连接时为空 */
+  connId?: number
+  /** 传输 id（CURLINFO_XFER_ID） */
+  xferId?: number
+}
+
+export interface TraceOptions {
+  /** 只记录这些类型，默认全部 */
+  types?: Array<CurlInfoType>
+  /** 收集到内存时 data 的总字节上限，超出后丢弃后续记录，默认不限制 */
+  maxBytes?: number
+}
+
+/** libcurl 的构建信息（curl_version_info） */
+export interface VersionInfo {
+  age: number
+  version: string
+  /** 0xXXYYZZ 格式的版本号 */
+  versionNum: number
+  host: string
+  /** CURL_VERSION_* 位掩码 */
+  features: number
+  /** 特性名称，如 HTTP2、HTTP3、brotli、ECH */
+  featureNames: Array<string>
+  protocols: Array<string>
+  sslVersion?: string
+  libzVersion?: string
+  aresVersion?: string
+  libidnVersion?: string
+  libsshVersion?: string
+  brotliVersion?: string
+  zstdVersion?: string
+  nghttp2Version?: string
+  /** HTTP/3 实现，如 "ngtcp2/1.2.0 nghttp3/1.1.0" */
+  quicVersion?: string
+  ngtcp2Version?: string
+  nghttp3Version?: string
+  cainfo?: string
+  capath?: string
+}
+
+export interface WsMessage {
+  data: Buffer
+  /** CurlWsFlag 的按位组合，Text / Binary / Close / Ping / Pong */
+  flags: number
+  /** CLOSE 帧的状态码 */
+  closeCode?: number
+  /** CLOSE 帧的原因 */
+  closeReason?: string
+}
@@ -394,24 +394,53 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.Curl = nativeBinding.Curl
+module.exports.CurlLibrary = nativeBinding.CurlLibrary
+module.exports.CurlMime = nativeBinding.CurlMime
 module.exports.CurlMulti = nativeBinding.CurlMulti
+module.exports.CurlShare = nativeBinding.CurlShare
+module.exports.CurlUrl = nativeBinding.CurlUrl
+module.exports.CurlWebSocket = nativeBinding.CurlWebSocket
+module.exports.HarRecorder = nativeBinding.HarRecorder
 module.exports.curlEasyError = nativeBinding.curlEasyError
 module.exports.CurlError = nativeBinding.CurlError
+module.exports.CurlHeaderError = nativeBinding.CurlHeaderError
+module.exports.CurlHeaderOrigin = nativeBinding.CurlHeaderOrigin
 module.exports.CurlHttpVersion = nativeBinding.CurlHttpVersion
 module.exports.CurlImpersonate = nativeBinding.CurlImpersonate
 module.exports.CurlInfo = nativeBinding.CurlInfo
+module.exports.CurlInfoType = nativeBinding.CurlInfoType
 module.exports.CurlIpResolve = nativeBinding.CurlIpResolve
 module.exports.CurlMOpt = nativeBinding.CurlMOpt
 module.exports.curlMultiError = nativeBinding.curlMultiError
 module.exports.CurlOpt = nativeBinding.CurlOpt
+module.exports.CurlOptType = nativeBinding.CurlOptType
+module.exports.curlShareError = nativeBinding.curlShareError
+module.exports.CurlShareLock = nativeBinding.CurlShareLock
+module.exports.CurlShOpt = nativeBinding.CurlShOpt
 module.exports.CurlSslVersion = nativeBinding.CurlSslVersion
+module.exports.CurlUError = nativeBinding.CurlUError
+module.exports.CurlUFlag = nativeBinding.CurlUFlag
+module.exports.CurlUPart = nativeBinding.CurlUPart
+module.exports.curlUrlError = nativeBinding.curlUrlError
 module.exports.CurlWsFlag = nativeBinding.CurlWsFlag
+module.exports.enableLogging = nativeBinding.enableLogging
+module.exports.ensureLibrary = nativeBinding.ensureLibrary
+module.exports.getCapabilities = nativeBinding.getCapabilities
+module.exports.getCurlOption = nativeBinding.getCurlOption
+module.exports.getCurlOptions = nativeBinding.getCurlOptions
 module.exports.getDefaultDirName = nativeBinding.getDefaultDirName
 module.exports.getDefaultLibPath = nativeBinding.getDefaultLibPath
 module.exports.getLibPath = nativeBinding.getLibPath
+module.exports.getLogLevel = nativeBinding.getLogLevel
 module.exports.getVersion = nativeBinding.getVersion
+module.exports.getVersionInfo = nativeBinding.getVersionInfo
 module.exports.globalCleanup = nativeBinding.globalCleanup
 module.exports.globalInit = nativeBinding.globalInit
+module.exports.globalTrace = nativeBinding.globalTrace
+module.exports.LogLevel = nativeBinding.LogLevel
 module.exports.setLibPath = nativeBinding.setLibPath
+module.exports.setLogLevel = nativeBinding.setLogLevel
+module.exports.setLogSink = nativeBinding.setLogSink
+module.exports.setModuleLogLevel = nativeBinding.setModuleLogLevel
 module.exports.socketIsReadable = nativeBinding.socketIsReadable
 module.exports.socketIsWritable = nativeBinding.socketIsWritable
@@ -1,8 +1,75 @@
 use napi::{Error, Status};
 use napi_derive::napi;
-use std::ffi::c_long;
+use std::ffi::{c_char, c_int, c_long, CStr, CString};
 
-use crate::loader::napi_load_library;
+use crate::loader::{napi_load_library, CurlFunctions, CurlVersionInfoData};
+
+// 请求的 curl_version_info_data 版本（CURLVERSION_ELEVENTH，含 feature_names）
+const CURLVERSION_ELEVENTH: c_int = 10;
+
+// feature_names 出现之前按 CURL_VERSION_* 位解码特性名称
+const FEATURE_BITS: [(u32, &str); 31] = [
+  (1 << 0, "IPv6"),
+  (1 << 1, "Kerberos4"),
+  (1 << 2, "SSL"),
+  (1 << 3, "libz"),
+  (1 << 4, "NTLM"),
+  (1 << 5, "GSS-Negotiate"),
+  (1 << 6, "Debug"),
+  (1 << 7, "AsynchDNS"),
+  (1 << 8, "SPNEGO"),
+  (1 << 9, "Largefile"),
+  (1 << 10, "IDN"),
+  (1 << 11, "SSPI"),
+  (1 << 12, "CharConv"),
+  (1 << 13, "TrackMemory"),
+  (1 << 14, "TLS-SRP"),
+  (1 << 15, "NTLM_WB"),
+  (1 << 16, "HTTP2"),
+  (1 << 17, "GSS-API"),
+  (1 << 18, "Kerberos"),
+  (1 << 19, "UnixSockets"),
+  (1 << 20, "PSL"),
+  (1 << 21, "HTTPS-proxy"),
+  (1 << 22, "MultiSSL"),
+  (1 << 23, "brotli"),
+  (1 << 24, "alt-svc"),
+  (1 << 25, "HTTP3"),
+  (1 << 26, "zstd"),
+  (1 << 27, "Unicode"),
+  (1 << 28, "HSTS"),
+  (1 << 29, "gsasl"),
+  (1 << 30, "threadsafe"),
+];
+
+/// libcurl 的构建信息（curl_version_info）
+#[napi(object)]
+pub struct VersionInfo {
+  pub age: i32,
+  pub version: String,
+  /// 0xXXYYZZ 格式的版本号
+  pub version_num: u32,
+  pub host: String,
+  /// CURL_VERSION_* 位掩码
+  pub features: u32,
+  /// 特性名称，如 HTTP2、HTTP3、brotli、ECH
+  pub feature_names: Vec<String>,
+  pub protocols: Vec<String>,
+  pub ssl_version: Option<String>,
+  pub libz_version: Option<String>,
+  pub ares_version: Option<String>,
+  pub libidn_version: Option<String>,
+  pub libssh_version: Option<String>,
+  pub brotli_version: Option<String>,
+  pub zstd_version: Option<String>,
+  pub nghttp2_version: Option<String>,
+  /// HTTP/3 实现，如 "ngtcp2/1.2.0 nghttp3/1.1.0"
+  pub quic_version: Option<String>,
+  pub ngtcp2_version: Option<String>,
+  pub nghttp3_version: Option<String>,
+  pub cainfo: Option<String>,
+  pub capath: Option<String>,
+}
 
 /// 全局初始化
 #[napi]
@@ -23,10 +90,35 @@ pub fn global_cleanup() -> napi::Result<()> {
   }
 }
 
+/// 开启 libcurl 组件级追踪（curl_global_trace），如 "tls,http/2"、"-all"
+///
+/// 输出经由 CURLOPT_DEBUGFUNCTION 交付，需配合 Curl.enableTrace 使用
+#[napi]
+pub fn global_trace(config: String) -> napi::Result<()> {
+  set_global_trace(napi_load_library()?, &config)
+}
+
+pub(crate) fn set_global_trace(lib: &'static CurlFunctions, config: &str) -> napi::Result<()> {
+  let config = CString::new(config)
+    .map_err(|_| Error::new(Status::InvalidArg, "Trace config contains a nul byte"))?;
+  let code = unsafe { (lib.global_trace.get()?)(config.as_ptr()) };
+  if code != 0 {
+    return Err(Error::from_reason(format!(
+      "curl_global_trace failed with code: {} message:{}",
+      code,
+      easy_strerror(lib, code)
+    )));
+  }
+  Ok(())
+}
+
 /// 获取 libcurl 版本信息
 #[napi]
 pub fn get_version() -> napi::Result<String> {
-  let lib = napi_load_library()?;
+  version(napi_load_library()?)
+}
+
+pub(crate) fn version(lib: &'static CurlFunctions) -> napi::Result<String> {
   unsafe {
     let version_ptr = (lib.version)(); // 修正：curl_version() 不需要参数
     if version_ptr.is_null() {
@@ -37,22 +129,193 @@ pub fn get_version() -> napi::Result<String> {
   }
 }
 
+/// 获取 libcurl 的结构化构建信息，用于检测 HTTP/3、ECH、brotli 等特性是否可用
+#[napi]
+pub fn get_version_info() -> napi::Result<VersionInfo> {
+  version_info(napi_load_library()?)
+}
+
+pub(crate) fn version_info(lib: &'static CurlFunctions) -> napi::Result<VersionInfo> {
+  let data = unsafe { (lib.version_info)(CURLVERSION_ELEVENTH) } as *const CurlVersionInfoData;
+  if data.is_null() {
+    return Err(Error::new(Status::GenericFailure, "Failed to get version info"));
+  }
+  let data = unsafe { &*data };
+  let age = data.age;
+  // 旧版本 libcurl 的结构体较短，只能读取其 age 范围内的字段
+  let field = |min_age: c_int, value: *const c_char| {
+    if age >= min_age {
+      unsafe { to_string(value) }
+    } else {
+      None
+    }
+  };
+
+  let features = data.features as u32;
+  let feature_names = if age >= CURLVERSION_ELEVENTH {
+    unsafe { to_string_list(data.feature_names) }
+  } else {
+    feature_names(features)
+  };
+  let quic_version = field(5, data.quic_version);
+  let quic_part = |prefix: &str| {
+    quic_version.as_deref().and_then(|quic| {
+      quic
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix(prefix))
+        .map(str::to_string)
+    })
+  };
+
+  Ok(VersionInfo {
+    age,
+    version: unsafe { to_string(data.version) }.unwrap_or_default(),
+    version_num: data.version_num,
+    host: unsafe { to_string(data.host) }.unwrap_or_default(),
+    features,
+    feature_names,
+    protocols: unsafe { to_string_list(data.protocols) },
+    ssl_version: field(0, data.ssl_version),
+    libz_version: field(0, data.libz_version),
+    ares_version: field(1, data.ares),
+    libidn_version: field(2, data.libidn),
+    libssh_version: field(3, data.libssh_version),
+    brotli_version: field(4, data.brotli_version),
+    zstd_version: field(7, data.zstd_version),
+    nghttp2_version: field(5, data.nghttp2_version),
+    ngtcp2_version: quic_part("ngtcp2/"),
+    nghttp3_version: quic_part("nghttp3/"),
+    quic_version,
+    cainfo: field(6, data.cainfo),
+    capath: field(6, data.capath),
+  })
+}
+
+fn feature_names(features: u32) -> Vec<String> {
+  FEATURE_BITS
+    .iter()
+    .filter(|(bit, _)| features & bit != 0)
+    .map(|(_, name)| name.to_string())
+    .collect()
+}
+
+unsafe fn to_string(value: *const c_char) -> Option<String> {
+  if value.is_null() {
+    return None;
+  }
+  Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+}
+
+// 以 NULL 结尾的字符串数组
+unsafe fn to_string_list(mut list: *const *const c_char) -> Vec<String> {
+  let mut items = Vec::new();
+  if list.is_null() {
+    return items;
+  }
+  while !(*list).is_null() {
+    items.extend(to_string(*list));
+    list = list.add(1);
+  }
+  items
+}
+
+/// 已加载的 libcurl 中可选符号的可用情况
+#[napi(object)]
+pub struct CurlCapability {
+  /// 符号名，如 curl_easy_impersonate
+  pub symbol: String,
+  pub available: bool,
+}
+
+/// 列出可选符号是否存在于已加载的 libcurl 中，缺少时相关 API 会抛出错误
+#[napi]
+pub fn get_capabilities() -> napi::Result<Vec<CurlCapability>> {
+  Ok(capabilities(napi_load_library()?))
+}
+
+pub(crate) fn capabilities(lib: &'static CurlFunctions) -> Vec<CurlCapability> {
+  lib
+    .optional_symbols()
+    .into_iter()
+    .map(|(symbol, available)| CurlCapability {
+      symbol: symbol.to_string(),
+      available,
+    })
+    .collect()
+}
+
 #[napi]
 pub fn curl_easy_error(code: i32) -> String {
   let lib = napi_load_library().expect("Failed to load libcurl library");
-  unsafe {
-    let ptr = (lib.easy_strerror)(code);
-    let cstr = std::ffi::CStr::from_ptr(ptr);
-    cstr.to_string_lossy().to_string()
-  }
+  easy_strerror(lib, code)
 }
 
 #[napi]
 pub fn curl_multi_error(code: i32) -> String {
   let lib = napi_load_library().expect("Failed to load libcurl library");
-  unsafe {
-    let ptr = (lib.multi_strerror)(code);
-    let cstr = std::ffi::CStr::from_ptr(ptr);
-    cstr.to_string_lossy().to_string()
+  multi_strerror(lib, code)
+}
+
+#[napi]
+pub fn curl_share_error(code: i32) -> String {
+  let lib = napi_load_library().expect("Failed to load libcurl library");
+  share_strerror(lib, code)
+}
+
+#[napi]
+pub fn curl_url_error(code: i32) -> String {
+  let lib = napi_load_library().expect("Failed to load libcurl library");
+  url_strerror(lib, code)
+}
+
+// 以下按句柄所属的库取错误信息，未指定库的 JS 函数使用全局默认库
+pub(crate) fn easy_strerror(lib: &'static CurlFunctions, code: i32) -> String {
+  unsafe { to_string((lib.easy_strerror)(code)) }.unwrap_or_default()
+}
+
+pub(crate) fn multi_strerror(lib: &'static CurlFunctions, code: i32) -> String {
+  unsafe { to_string((lib.multi_strerror)(code)) }.unwrap_or_default()
+}
+
+pub(crate) fn share_strerror(lib: &'static CurlFunctions, code: i32) -> String {
+  unsafe { to_string((lib.share_strerror)(code)) }.unwrap_or_default()
+}
+
+pub(crate) fn url_strerror(lib: &'static CurlFunctions, code: i32) -> String {
+  let Ok(url_strerror) = lib.url_strerror.get() else {
+    return format!("CURLUcode {}", code);
+  };
+  unsafe { to_string(url_strerror(code)) }.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decodes_feature_bits() {
+    assert!(feature_names(0).is_empty());
+    assert_eq!(
+      feature_names(1 << 0 | 1 << 16 | 1 << 25),
+      ["IPv6", "HTTP2", "HTTP3"]
+    );
+    assert_eq!(feature_names(1 << 23 | 1 << 26), ["brotli", "zstd"]);
+  }
+
+  #[test]
+  fn ignores_unknown_feature_bits() {
+    let unknown = FEATURE_BITS
+      .iter()
+      .fold(u32::MAX, |bits, (bit, _)| bits & !bit);
+    assert_ne!(unknown, 0);
+    assert!(feature_names(unknown).is_empty());
+    assert_eq!(feature_names(u32::MAX).len(), FEATURE_BITS.len());
+  }
+
+  #[test]
+  fn reads_null_terminated_list() {
+    let items = [c"HTTP2".as_ptr(), c"zstd".as_ptr(), std::ptr::null()];
+    assert_eq!(unsafe { to_string_list(items.as_ptr()) }, ["HTTP2", "zstd"]);
+    assert!(unsafe { to_string_list(std::ptr::null()) }.is_empty());
   }
 }
@@ -3,11 +3,32 @@
 
 use napi_derive::napi;
 
-// CURL 选项常量
-#[repr(u32)]
-#[derive(Debug)]
-#[napi]
-pub enum CurlOpt {
+// 由同一份列表生成 CurlOpt 与按 id 的查找，新增选项时两者保持一致
+macro_rules! curl_opt {
+    ($($name:ident = $value:literal,)*) => {
+        // CURL 选项常量
+        #[repr(u32)]
+        #[derive(Debug)]
+        #[napi]
+        pub enum CurlOpt {
+            $($name = $value,)*
+        }
+
+        impl TryFrom<i32> for CurlOpt {
+            type Error = i32;
+
+            /// 按选项 id 查找，不属于 CurlOpt 时返回原 id
+            fn try_from(id: i32) -> Result<Self, i32> {
+                Ok(match id {
+                    $($value => CurlOpt::$name,)*
+                    _ => return Err(id),
+                })
+            }
+        }
+    };
+}
+
+curl_opt! {
     WriteData = 10001,
     Url = 10002,
     Port = 3,
@@ -342,7 +363,7 @@ pub enum CurlOpt {
 
 // CURL 信息常量
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[napi]
 pub enum CurlInfo {
     Text = 0,
@@ -417,6 +438,37 @@ pub enum CurlInfo {
     LastOne = 70,
 }
 
+// CurlInfo 值的高位表示 curl_easy_getinfo 写入的 C 类型
+pub const CURLINFO_STRING: u32 = 0x100000;
+pub const CURLINFO_LONG: u32 = 0x200000;
+pub const CURLINFO_DOUBLE: u32 = 0x300000;
+pub const CURLINFO_SLIST: u32 = 0x400000;
+pub const CURLINFO_PTR: u32 = 0x400000;
+pub const CURLINFO_SOCKET: u32 = 0x500000;
+pub const CURLINFO_OFF_T: u32 = 0x600000;
+pub const CURLINFO_TYPEMASK: u32 = 0xf00000;
+
+impl CurlInfo {
+    /// C 类型位，即 CURLINFO_STRING、CURLINFO_LONG 等
+    pub fn type_bits(self) -> u32 {
+        self as u32 & CURLINFO_TYPEMASK
+    }
+}
+
+// CURLOPT_DEBUGFUNCTION 回调的数据类型
+#[repr(u32)]
+#[derive(Debug, Clone, Copy)]
+#[napi]
+pub enum CurlInfoType {
+    Text = 0,
+    HeaderIn = 1,
+    HeaderOut = 2,
+    DataIn = 3,
+    DataOut = 4,
+    SslDataIn = 5,
+    SslDataOut = 6,
+}
+
 // CURL Multi 选项常量
 #[repr(u32)]
 #[derive(Debug)]
@@ -575,6 +627,156 @@ pub enum CurlWsFlag {
     Close = 8,
     Ping = 16,
     Offset = 32,
+    Pong = 64,
+}
+
+// 响应头来源常量，可按位组合
+#[repr(u32)]
+#[derive(Debug)]
+#[napi]
+pub enum CurlHeaderOrigin {
+    Header = 1,
+    Trailer = 2,
+    Connect = 4,
+    Informational = 8,
+    Pseudo = 16,
+}
+
+// curl_easy_header 返回码
+#[repr(u32)]
+#[derive(Debug)]
+#[napi]
+pub enum CurlHeaderError {
+    Ok = 0,
+    BadIndex = 1,
+    Missing = 2,
+    NoHeaders = 3,
+    NoRequest = 4,
+    OutOfMemory = 5,
+    BadArgument = 6,
+    NotBuiltIn = 7,
+}
+
+// 共享句柄可共享的数据类型（curl_lock_data）
+#[repr(u32)]
+#[derive(Debug, Clone, Copy)]
+#[napi]
+pub enum CurlShareLock {
+    Share = 1,
+    Cookie = 2,
+    Dns = 3,
+    SslSession = 4,
+    Connect = 5,
+    Psl = 6,
+    Hsts = 7,
+}
+
+// 共享句柄选项
+#[repr(u32)]
+#[derive(Debug)]
+#[napi]
+pub enum CurlShOpt {
+    Share = 1,
+    Unshare = 2,
+    LockFunc = 3,
+    UnlockFunc = 4,
+    UserData = 5,
+}
+
+// 选项值的类型（curl_easytype）
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[napi]
+pub enum CurlOptType {
+    Long = 0,
+    Values = 1,
+    OffT = 2,
+    Object = 3,
+    String = 4,
+    Slist = 5,
+    CbPtr = 6,
+    Blob = 7,
+    Function = 8,
+}
+
+// curl_url 的 URL 组成部分
+#[repr(u32)]
+#[derive(Debug, Clone, Copy)]
+#[napi]
+pub enum CurlUPart {
+    Url = 0,
+    Scheme = 1,
+    User = 2,
+    Password = 3,
+    Options = 4,
+    Host = 5,
+    Port = 6,
+    Path = 7,
+    Query = 8,
+    Fragment = 9,
+    ZoneId = 10,
+}
+
+// curl_url_get / curl_url_set 标志，可按位组合
+#[repr(u32)]
+#[derive(Debug)]
+#[napi]
+pub enum CurlUFlag {
+    DefaultPort = 1,
+    NoDefaultPort = 2,
+    DefaultScheme = 4,
+    NonSupportScheme = 8,
+    PathAsIs = 16,
+    DisallowUser = 32,
+    UrlDecode = 64,
+    UrlEncode = 128,
+    AppendQuery = 256,
+    GuessScheme = 512,
+    NoAuthority = 1024,
+    AllowSpace = 2048,
+    Punycode = 4096,
+    PunyToIdn = 8192,
+    GetEmpty = 16384,
+    NoGuessScheme = 32768,
+}
+
+// curl_url 返回码
+#[repr(u32)]
+#[derive(Debug)]
+#[napi]
+pub enum CurlUError {
+    Ok = 0,
+    BadHandle = 1,
+    BadPartpointer = 2,
+    MalformedInput = 3,
+    BadPortNumber = 4,
+    UnsupportedScheme = 5,
+    UrlDecode = 6,
+    OutOfMemory = 7,
+    UserNotAllowed = 8,
+    UnknownPart = 9,
+    NoScheme = 10,
+    NoUser = 11,
+    NoPassword = 12,
+    NoOptions = 13,
+    NoHost = 14,
+    NoPort = 15,
+    NoQuery = 16,
+    NoFragment = 17,
+    NoZoneId = 18,
+    BadFileUrl = 19,
+    BadFragment = 20,
+    BadHostname = 21,
+    BadIpv6 = 22,
+    BadLogin = 23,
+    BadPassword = 24,
+    BadPath = 25,
+    BadQuery = 26,
+    BadScheme = 27,
+    BadSlashes = 28,
+    BadUser = 29,
+    LackIdn = 30,
+    TooLarge = 31,
 }
 
 // SSL 版本常量
@@ -1,18 +1,75 @@
-use napi::bindgen_prelude::{AsyncTask, Buffer, Either3};
+use napi::bindgen_prelude::{AsyncTask, BigInt, Buffer, Either5, Reference};
+use napi::threadsafe_function::ThreadsafeFunction;
 use napi::{Either, Error, Result, Status};
 use napi_derive::napi;
 use std::cell::UnsafeCell;
-use std::os::raw::{c_char, c_int, c_long, c_void};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_double, c_int, c_long, c_void};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::api::curl_easy_error;
-use crate::loader::CurlSlistNode;
-use crate::log_info;
+use crate::api::easy_strerror;
+use crate::info::{certinfo_strings, slist_strings, CurlInfoValue};
+use crate::loader::{CurlBlob, CurlCertInfo, CurlHeader, CurlSlistNode, ReadCallback};
+use crate::{log_info, log_warn};
+use crate::library::{check_same_library, resolve_library, CurlLibrary};
+use crate::mime::{CurlMime, MimeData};
+use crate::options::{find_by_name, mismatch, option_type, CurlOptValue};
+use crate::share::{CurlShare, ShareData};
+use crate::url::{CurlUrl, UrlData};
+use crate::response::{parse_response_hops, ResponseHop};
+use crate::har::{HarRecorder, HarStore, HarTransfer};
+use crate::stats::{read_stats, CurlStats};
+use crate::trace::{debug_callback, TraceCallback, TraceEntry, TraceOptions, TraceState};
+use crate::stream::{
+  stream_data, stream_read, stream_seek, xferinfo_callback, BodyCallback, BodyStream, ProgressCallback, TransferControl,
+  UploadStream, DEFAULT_HIGH_WATER_MARK,
+};
+use crate::socket::CurlSocket;
 use crate::utils::get_ptr_address;
+use crate::websocket::WsShared;
 use crate::{
-  constants::{CurlInfo, CurlOpt},
-  loader::{napi_load_library, CurlFunctions, CurlHandle, CurlSlist},
+  constants::{
+    CurlHeaderError, CurlHeaderOrigin, CurlInfo, CurlOpt, CurlOptType, CURLINFO_DOUBLE,
+    CURLINFO_LONG, CURLINFO_OFF_T, CURLINFO_PTR, CURLINFO_SLIST, CURLINFO_SOCKET, CURLINFO_STRING,
+  },
+  loader::{CurlFunctions, CurlHandle, CurlSlist},
 };
 
+// curl_easy_header 返回的单个响应头
+#[napi(object)]
+pub struct CurlHeaderEntry {
+  pub name: String,
+  pub value: String,
+  /// 同名头部的总数
+  pub amount: u32,
+  /// 当前头部在同名头部中的序号
+  pub index: u32,
+  /// 来源，见 CurlHeaderOrigin
+  pub origin: u32,
+}
+
+impl CurlHeaderEntry {
+  unsafe fn from_raw(header: &CurlHeader) -> Self {
+    let text = |ptr: *mut c_char| {
+      if ptr.is_null() {
+        String::new()
+      } else {
+        std::ffi::CStr::from_ptr(ptr).to_string_lossy().to_string()
+      }
+    };
+    CurlHeaderEntry {
+      name: text(header.name),
+      value: text(header.value),
+      amount: header.amount as u32,
+      index: header.index as u32,
+      origin: header.origin,
+    }
+  }
+}
+
 // 简单的内存写入回调
 extern "C" fn write_data(
   ptr: *mut c_char,
@@ -32,6 +89,21 @@ extern "C" fn write_data(
 // 使用 AsyncTask 的执行任务结构，跨线程仅传递 usize 句柄，避免原始指针的 Send 约束
 pub struct PerformTask {
   handle: usize,
+  lib: &'static CurlFunctions,
+  // 防止传输期间 Curl 被回收；结束后在 JS 线程中记录 HAR
+  curl: Reference<Curl>,
+  control: Arc<TransferControl>,
+  code: c_int,
+}
+
+// curl 只在 JS 线程中使用和释放：compute 在工作线程中只访问 handle
+unsafe impl Send for PerformTask {}
+
+impl PerformTask {
+  fn finish(&self) {
+    self.curl.control.performing.store(false, Ordering::SeqCst);
+    self.curl.record_har(self.code, None);
+  }
 }
 
 impl napi::Task for PerformTask {
@@ -39,25 +111,26 @@ impl napi::Task for PerformTask {
   type JsValue = ();
 
   fn compute(&mut self) -> napi::Result<Self::Output> {
-    unsafe {
-      let lib = napi_load_library()?;
-      let code = (lib.easy_perform)(self.handle as CurlHandle);
-      if code != 0 {
-        let error = curl_easy_error(code);
-        return Err(Error::from_reason(format!(
-          "failed with code: {} message:{}",
-          code, error
-        )));
-      }
-      Ok(())
+    self.code = unsafe { (self.lib.easy_perform)(self.handle as CurlHandle) };
+    // 结束信号在任务结算前投递，排在所有数据块之后
+    self.control.end_body(self.code);
+    if self.code != 0 {
+      let error = easy_strerror(self.lib, self.code);
+      return Err(Error::from_reason(format!(
+        "failed with code: {} message:{}",
+        self.code, error
+      )));
     }
+    Ok(())
   }
 
   fn resolve(&mut self, _env: napi::Env, _output: Self::Output) -> napi::Result<Self::JsValue> {
+    self.finish();
     Ok(())
   }
 
   fn reject(&mut self, _env: napi::Env, err: Error) -> Result<Self::JsValue> {
+    self.finish();
     Err(err)
   }
 }
@@ -71,6 +144,25 @@ pub struct Curl {
   content_buffer: UnsafeCell<Vec<u8>>,
   req_header: UnsafeCell<Option<CurlSlist>>,
   req_body: UnsafeCell<Vec<u8>>,
+  // 通过 setOption 设置的链表选项，需存活到被替换或 easy handle 释放之后
+  opt_lists: UnsafeCell<HashMap<c_int, CurlSlist>>,
+  body_stream: UnsafeCell<Option<Box<BodyStream>>>,
+  upload_stream: UnsafeCell<Option<Box<UploadStream>>>,
+  // 通过 CURLOPT_MIMEPOST 挂载的表单，需存活到 easy handle 释放之后
+  mime: UnsafeCell<Option<Arc<MimeData>>>,
+  // 通过 CURLOPT_SHARE 使用的共享句柄
+  share: UnsafeCell<Option<Arc<ShareData>>>,
+  // 通过 CURLOPT_CURLU 使用的 URL 句柄
+  curlu: UnsafeCell<Option<Arc<UrlData>>>,
+  // 通过 CURLOPT_DEBUGDATA 传给 libcurl 的线路追踪
+  trace: UnsafeCell<Option<Box<TraceState>>>,
+  // 用户设置的 CURLOPT_VERBOSE；追踪期间强制开启，停止后恢复
+  verbose: UnsafeCell<bool>,
+  // setHarRecorder 挂载的 HAR 记录器
+  har: UnsafeCell<Option<Arc<HarStore>>>,
+  // 基于该 Curl 的 WebSocket，释放 easy handle 前需先停止其收发
+  websocket: UnsafeCell<Option<Arc<WsShared>>>,
+  control: Arc<TransferControl>,
 }
 
 // UnsafeCell 需要手动实现 Send 和 Sync
@@ -79,53 +171,198 @@ unsafe impl Sync for Curl {}
 
 #[napi]
 impl Curl {
+  /// library 默认为全局库
   #[napi(constructor)]
-  pub fn new() -> napi::Result<Self> {
+  pub fn new(library: Option<&CurlLibrary>) -> napi::Result<Self> {
+    let lib = resolve_library(library)?;
+    let handle = unsafe { (lib.easy_init)() };
+    Self::from_handle(lib, handle)
+  }
+
+  pub(crate) fn from_handle(
+    lib: &'static CurlFunctions,
+    handle: CurlHandle,
+  ) -> napi::Result<Self> {
+    if handle.is_null() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Failed to initialize curl handle",
+      ));
+    }
+
+    let curl = Curl {
+      closed: false,
+      lib,
+      handle,
+      header_buffer: UnsafeCell::new(Vec::new()),
+      content_buffer: UnsafeCell::new(Vec::new()),
+      req_header: UnsafeCell::new(None), // 初始化 headers 列表
+      req_body: UnsafeCell::new(Vec::new()),
+      opt_lists: UnsafeCell::new(HashMap::new()),
+      body_stream: UnsafeCell::new(None),
+      upload_stream: UnsafeCell::new(None),
+      mime: UnsafeCell::new(None),
+      share: UnsafeCell::new(None),
+      curlu: UnsafeCell::new(None),
+      trace: UnsafeCell::new(None),
+      verbose: UnsafeCell::new(false),
+      har: UnsafeCell::new(None),
+      websocket: UnsafeCell::new(None),
+      control: Arc::new(TransferControl::new(handle, lib)),
+    };
+
+    Ok(curl)
+  }
+
+  /// 复制当前 Curl 的全部选项，得到独立的新实例
+  ///
+  /// 请求头链表与请求体会深拷贝；流式响应体、流式请求体与进度回调不会被复制
+  #[napi]
+  pub fn duplicate(&self) -> Result<Curl> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "easy_duphandle");
+    let handle = unsafe { (self.lib.easy_duphandle)(self.handle) };
+    let curl = Self::from_handle(self.lib, handle)?;
     unsafe {
-      let lib = napi_load_library()?;
-
-      let handle = (lib.easy_init)();
-      if handle.is_null() {
-        return Err(Error::new(
-          Status::GenericFailure,
-          "Failed to initialize curl handle",
-        ));
-      }
-
-      let curl = Curl {
-        closed: false,
-        lib,
-        handle,
-        header_buffer: UnsafeCell::new(Vec::new()),
-        content_buffer: UnsafeCell::new(Vec::new()),
-        req_header: UnsafeCell::new(None), // 初始化 headers 列表
-        req_body: UnsafeCell::new(Vec::new()),
-      };
+      // duphandle 只复制指针，自有数据需重新指向副本
+      if let Some(list) = *self.req_header.get() {
+        let copy = self.copy_slist(list);
+        *curl.req_header.get() = Some(copy);
+        curl.set_opt(CurlOpt::HttpHeader, copy as *const c_void)?;
+      }
+      for (&id, &list) in &*self.opt_lists.get() {
+        let copy = self.copy_slist(list);
+        (*curl.opt_lists.get()).insert(id, copy);
+        curl.set_opt_id(id, copy as *const c_void)?;
+      }
+      if (*self.upload_stream.get()).is_some() {
+        // 流式请求体不随副本复制，清除指向原实例的读回调
+        curl.clear_upload_options()?;
+      }
+      *curl.req_body.get() = (*self.req_body.get()).clone();
+      curl.restore_body()?;
+      *curl.verbose.get() = *self.verbose.get();
+      if (*self.trace.get()).is_some() {
+        // 追踪与 HAR 采集属于原实例，副本需要时重新开启
+        curl.clear_trace_options()?;
+      }
+      // 表单、共享句柄与 CurlUrl 仍被副本引用，需要一同持有
+      *curl.mime.get() = (*self.mime.get()).clone();
+      *curl.share.get() = (*self.share.get()).clone();
+      *curl.curlu.get() = (*self.curlu.get()).clone();
+    }
+    Ok(curl)
+  }
 
-      Ok(curl)
+  unsafe fn copy_slist(&self, list: CurlSlist) -> CurlSlist {
+    let mut copy: CurlSlist = std::ptr::null_mut();
+    let mut current = list as *mut CurlSlistNode;
+    while !current.is_null() {
+      let node = &*current;
+      if !node.data.is_null() {
+        copy = (self.lib.slist_append)(copy, node.data);
+      }
+      current = node.next;
     }
+    copy
   }
 
   /// 初始化数据回调
   #[napi]
   pub fn init(&self) {
-    log_info!("Curl", "Initializing curl data callbacks");
+    log_info!("Curl", handle = self.id(); "Initializing curl data callbacks");
     unsafe {
       (*self.header_buffer.get()).clear();
       (*self.content_buffer.get()).clear();
-      // 设置写入函数
-      (self.lib.easy_setopt)(
-        self.handle,
-        CurlOpt::WriteFunction as c_int,
-        write_data as *const c_void,
-      );
+      self.control.reset();
+      if let Some(stream) = &*self.body_stream.get() {
+        // 流式模式：响应体直接投递给 JS
+        stream.reset();
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::WriteFunction as c_int,
+          stream_data as *const c_void,
+        );
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::WriteData as c_int,
+          &**stream as *const BodyStream as *const c_void,
+        );
+      } else {
+        // 设置写入函数
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::WriteFunction as c_int,
+          write_data as *const c_void,
+        );
 
-      // 设置响应体数据存储
-      (self.lib.easy_setopt)(
-        self.handle,
-        CurlOpt::WriteData as c_int,
-        self.content_buffer.get() as *mut c_void,
-      );
+        // 设置响应体数据存储
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::WriteData as c_int,
+          self.content_buffer.get() as *mut c_void,
+        );
+      }
+
+      if let Some(upload) = &*self.upload_stream.get() {
+        // 流式请求体：size 未知时 libcurl 自动使用 chunked 编码
+        upload.rewind();
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::ReadFunction as c_int,
+          stream_read as ReadCallback as *const c_void,
+        );
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::ReadData as c_int,
+          &**upload as *const UploadStream as *const c_void,
+        );
+        // 重定向、认证重试需要重发请求体时回到开头
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::SeekFunction as c_int,
+          stream_seek as *const c_void,
+        );
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::SeekData as c_int,
+          &**upload as *const UploadStream as *const c_void,
+        );
+        // 以 POST 发送，PostFields 优先于读回调，需先清除 setBody 设置的请求体
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::PostFields as c_int,
+          std::ptr::null::<c_void>(),
+        );
+        let _ = self.set_option(CurlOpt::Post, Either5::C(true));
+        let _ = self.set_option(
+          CurlOpt::PostFieldSizeLarge,
+          Either5::B(upload.size.unwrap_or(-1)),
+        );
+      }
+
+      // 进度回调，以及暂停的流式传输在工作线程中的恢复，都依赖 xferinfo 回调
+      if self.is_streaming() || self.control.has_progress() {
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::XferInfoFunction as c_int,
+          xferinfo_callback as *const c_void,
+        );
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::XferInfoData as c_int,
+          Arc::as_ptr(&self.control) as *const c_void,
+        );
+        let _ = self.set_option(CurlOpt::NoProgress, Either5::C(false));
+      } else {
+        (self.lib.easy_setopt)(
+          self.handle,
+          CurlOpt::XferInfoFunction as c_int,
+          std::ptr::null::<c_void>(),
+        );
+        let _ = self.set_option(CurlOpt::NoProgress, Either5::C(true));
+      }
 
       // 设置头部写入函数
       (self.lib.easy_setopt)(
@@ -171,6 +408,7 @@ impl Curl {
   #[napi]
   pub fn set_headers_raw(&self, headers: Vec<String>) -> Result<()> {
     self.check_close()?;
+    self.check_idle()?;
     // 释放旧的 header 链表
     unsafe {
       if let Some(list) = *self.req_header.get() {
@@ -199,33 +437,133 @@ impl Curl {
   }
 
   pub fn set_opt(&self, option: CurlOpt, value: *const c_void) -> Result<()> {
+    self.set_opt_id(option as c_int, value)
+  }
+
+  pub(crate) fn set_opt_id(&self, option: c_int, value: *const c_void) -> Result<()> {
     self.check_close()?;
     log_info!(
       "Curl",
+      handle = self.id();
       "Setting option: {:?} with value: {:?}",
       option,
       value
     );
-    self.result(unsafe { (self.lib.easy_setopt)(self.handle, option as c_int, value) })
+    self.result(unsafe { (self.lib.easy_setopt)(self.handle, option, value) })
   }
 
-  #[napi]
-  pub fn set_option(&self, option: CurlOpt, value: Either3<String, i64, bool>) -> Result<()> {
-    match value {
-      Either3::A(string_value) => {
-        let c_str = std::ffi::CString::new(string_value).unwrap();
-        self.set_opt(option, c_str.as_ptr() as *const c_void)
+  /// 按选项类型校验并设置，类型不符时抛出 InvalidArg
+  #[napi(ts_args_type = "option: CurlOpt, value: string | number | boolean | Array<string> | Buffer")]
+  pub fn set_option(&self, option: CurlOpt, value: CurlOptValue) -> Result<()> {
+    self.set_typed_option(option as c_int, value)
+  }
+
+  /// 按 libcurl 选项名设置，如 "TIMEOUT_MS"，可带 CURLOPT_ 前缀，不区分大小写
+  #[napi(ts_args_type = "name: string, value: string | number | boolean | Array<string> | Buffer")]
+  pub fn set_option_by_name(&self, name: String, value: CurlOptValue) -> Result<()> {
+    let option = find_by_name(self.lib, &name)?;
+    if option.is_null() {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!("Unknown curl option: {}", name),
+      ));
+    }
+    self.set_typed_option(unsafe { (*option).id }, value)
+  }
+
+  pub(crate) fn set_typed_option(&self, id: c_int, value: CurlOptValue) -> Result<()> {
+    self.check_close()?;
+    if id == CurlOpt::Verbose as c_int {
+      let verbose = match &value {
+        Either5::B(long_value) => Some(*long_value != 0),
+        Either5::C(bool_value) => Some(*bool_value),
+        _ => None,
+      };
+      if let Some(verbose) = verbose {
+        unsafe { *self.verbose.get() = verbose };
+        // 追踪依赖 VERBOSE，停止追踪时再应用
+        if self.is_tracing() {
+          return Ok(());
+        }
+      }
+    }
+    let opt_type = option_type(self.lib, id, &value);
+    match (opt_type, value) {
+      (CurlOptType::Long | CurlOptType::Values, Either5::B(long_value)) => {
+        self.set_opt_id(id, long_value as c_long as *const c_void)
       }
-      Either3::B(long_value) => self.set_opt(option, long_value as *const c_void),
-      Either3::C(bool_value) => {
-        self.set_opt(option, if bool_value { 1 } else { 0 } as *const c_void)
+      (CurlOptType::Long | CurlOptType::Values, Either5::C(bool_value)) => {
+        self.set_opt_id(id, bool_value as c_long as *const c_void)
       }
+      (CurlOptType::OffT, Either5::B(off_value)) => self.set_opt_id(id, off_value as *const c_void),
+      // libcurl 不复制 POSTFIELDS，由 set_body 持有数据
+      (CurlOptType::Object, Either5::A(string_value)) if id == CurlOpt::PostFields as c_int => {
+        self.set_body(Either::A(string_value))
+      }
+      (CurlOptType::Object, Either5::E(buffer_value)) if id == CurlOpt::PostFields as c_int => {
+        self.set_body(Either::B(buffer_value))
+      }
+      (CurlOptType::String, Either5::A(string_value)) => {
+        let c_str = CString::new(string_value)
+          .map_err(|_| Error::new(Status::InvalidArg, "String contains a nul byte"))?;
+        self.set_opt_id(id, c_str.as_ptr() as *const c_void)
+      }
+      (CurlOptType::Slist, Either5::D(items)) => self.set_slist(id, items),
+      (CurlOptType::Blob, Either5::A(string_value)) => self.set_blob(id, string_value.as_bytes()),
+      (CurlOptType::Blob, Either5::E(buffer_value)) => self.set_blob(id, &buffer_value),
+      (opt_type, value) => Err(mismatch(id, opt_type, &value)),
+    }
+  }
+
+  // CURL_BLOB_COPY：libcurl 复制数据，调用返回后即可释放
+  fn set_blob(&self, id: c_int, data: &[u8]) -> Result<()> {
+    let blob = CurlBlob {
+      data: data.as_ptr() as *mut c_void,
+      len: data.len(),
+      flags: 1,
+    };
+    self.set_opt_id(id, &blob as *const CurlBlob as *const c_void)
+  }
+
+  // 构造 curl_slist 并持有到被替换或 Curl 释放，空数组清除该选项
+  fn set_slist(&self, id: c_int, items: Vec<String>) -> Result<()> {
+    // 旧链表会被释放
+    self.check_idle()?;
+    let mut list: CurlSlist = std::ptr::null_mut();
+    for item in items {
+      let Ok(item) = CString::new(item) else {
+        unsafe { (self.lib.slist_free_all)(list) };
+        return Err(Error::new(Status::InvalidArg, "String contains a nul byte"));
+      };
+      let next = unsafe { (self.lib.slist_append)(list, item.as_ptr()) };
+      if next.is_null() {
+        unsafe { (self.lib.slist_free_all)(list) };
+        return Err(Error::new(Status::GenericFailure, "Failed to create list"));
+      }
+      list = next;
+    }
+    if let Err(err) = self.set_opt_id(id, list as *const c_void) {
+      unsafe { (self.lib.slist_free_all)(list) };
+      return Err(err);
+    }
+    let old = unsafe { (*self.opt_lists.get()).insert(id, list) };
+    if let Some(old) = old.filter(|old| !old.is_null()) {
+      unsafe { (self.lib.slist_free_all)(old) };
+    }
+    Ok(())
+  }
+
+  fn free_opt_lists(&self) {
+    let lists = unsafe { std::mem::take(&mut *self.opt_lists.get()) };
+    for list in lists.into_values().filter(|list| !list.is_null()) {
+      unsafe { (self.lib.slist_free_all)(list) };
     }
   }
 
   #[napi]
   pub fn set_body(&self, value: Either<String, Buffer>) -> Result<()> {
     self.check_close()?;
+    self.check_idle()?;
 
     let bytes = match value {
       Either::A(string_value) => {
@@ -249,7 +587,7 @@ impl Curl {
   #[napi]
   pub fn set_opt_str_list(&self, option: CurlOpt, arrays: Vec<String>) -> Result<()> {
     self.check_close()?;
-    self.set_opt(option, arrays.as_ptr() as *const c_void)
+    self.set_slist(option as c_int, arrays)
   }
 
   fn result(&self, code: i32) -> Result<()> {
@@ -265,42 +603,116 @@ impl Curl {
 
   pub fn get_info(&self, info: CurlInfo, value: *mut c_void) -> Result<()> {
     self.check_close()?;
-    log_info!("Curl", "{:?}Get info: {:?} ", self.id(), info);
+    self.check_performing()?;
+    log_info!("Curl", handle = self.id(); "Get info: {:?}", info);
     self.result(unsafe { (self.lib.easy_getinfo)(self.handle, info as c_int, value) })
   }
 
-  /// 获取响应码
+  /// 获取数值信息，LONG、DOUBLE、OFF_T、SOCKET 类型均按其 C 类型读取
+  ///
+  /// 无效的 socket 返回 -1
+  #[napi]
+  pub fn get_info_number(&self, option: CurlInfo) -> Result<f64> {
+    match option.type_bits() {
+      CURLINFO_LONG => {
+        let mut value: c_long = 0;
+        self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+        Ok(value as f64)
+      }
+      CURLINFO_DOUBLE => {
+        let mut value: c_double = 0.0;
+        self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+        Ok(value)
+      }
+      CURLINFO_OFF_T => {
+        let mut value: i64 = 0;
+        self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+        Ok(value as f64)
+      }
+      CURLINFO_SOCKET => {
+        let mut value: CurlSocket = 0;
+        self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+        // CURL_SOCKET_BAD 在 Windows 上为 INVALID_SOCKET
+        Ok(value as i64 as f64)
+      }
+      _ => Err(info_type_error(option, "number")),
+    }
+  }
+
+  /// 获取字符串信息，未设置时为 null
   #[napi]
-  pub fn get_info_number(&self, option: CurlInfo) -> Result<i64> {
-    let mut response_code: c_long = 0;
-    self.get_info(option, &mut response_code as *mut _ as *mut c_void)?;
-    Ok(response_code as i64)
+  pub fn get_info_string(&self, option: CurlInfo) -> Result<Option<String>> {
+    // CURLINFO_PRIVATE 由 CurlMulti 保存传输 id，并非字符串
+    if option.type_bits() != CURLINFO_STRING || matches!(option, CurlInfo::Private) {
+      return Err(info_type_error(option, "string"));
+    }
+    let mut value: *mut c_char = std::ptr::null_mut();
+    self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+    if value.is_null() {
+      return Ok(None);
+    }
+    let cstr = unsafe { std::ffi::CStr::from_ptr(value) };
+    Ok(Some(cstr.to_string_lossy().to_string()))
+  }
+
+  /// 按信息的类型位读取并转换为对应的 JS 类型
+  ///
+  /// STRING 为字符串或 null，LONG、DOUBLE、SOCKET 为数字，OFF_T 超出安全整数范围时为 BigInt，
+  /// SLIST 为字符串数组，CertInfo 为每个证书的 "名称:值" 数组
+  #[napi(ts_return_type = "string | number | bigint | Array<string> | Array<Array<string>> | null")]
+  pub fn get_info_value(&self, option: CurlInfo) -> Result<Option<CurlInfoValue>> {
+    match (option.type_bits(), option) {
+      (CURLINFO_STRING, _) => Ok(self.get_info_string(option)?.map(Either5::A)),
+      (CURLINFO_OFF_T, _) => {
+        let mut value: i64 = 0;
+        self.get_info(option, &mut value as *mut _ as *mut c_void)?;
+        if value.unsigned_abs() <= MAX_SAFE_INTEGER {
+          Ok(Some(Either5::B(value as f64)))
+        } else {
+          Ok(Some(Either5::C(BigInt::from(value))))
+        }
+      }
+      (CURLINFO_LONG | CURLINFO_DOUBLE | CURLINFO_SOCKET, _) => {
+        Ok(Some(Either5::B(self.get_info_number(option)?)))
+      }
+      (CURLINFO_PTR, CurlInfo::CertInfo) => {
+        let mut certinfo: *mut CurlCertInfo = std::ptr::null_mut();
+        self.get_info(option, &mut certinfo as *mut _ as *mut c_void)?;
+        Ok(Some(Either5::E(unsafe { certinfo_strings(certinfo) })))
+      }
+      (CURLINFO_SLIST, CurlInfo::SslEngines | CurlInfo::CookieList) => {
+        Ok(Some(Either5::D(self.get_info_list(option)?)))
+      }
+      _ => Err(info_type_error(option, "value")),
+    }
   }
 
-  /// 获取字符串信息
+  /// 获取传输统计，各项信息按其 C 类型读取
   #[napi]
-  pub fn get_info_string(&self, option: CurlInfo) -> Result<String> {
-    let mut url_ptr: *mut c_char = std::ptr::null_mut();
-    self.get_info(option, &mut url_ptr as *mut _ as *mut c_void)?;
-    let cstr = unsafe { std::ffi::CStr::from_ptr(url_ptr) };
-    Ok(cstr.to_string_lossy().to_string())
+  pub fn get_stats(&self) -> Result<CurlStats> {
+    self.check_close()?;
+    self.check_performing()?;
+    log_info!("Curl", handle = self.id(); "Get stats");
+    Ok(unsafe { read_stats(self.lib, self.handle) })
   }
 
   /// 模拟浏览器
   #[napi]
   pub fn impersonate(&self, target: String, default_headers: Option<bool>) -> Result<()> {
     self.check_close()?;
+    let impersonate = self.lib.easy_impersonate.get()?;
     let target_cstr = std::ffi::CString::new(target.clone()).unwrap();
     let use_default_headers = default_headers.unwrap_or(true);
     log_info!(
       "Curl",
+      handle = self.id();
       "Impersonating as: {} with default headers: {}",
       target,
       use_default_headers
     );
 
     self.result(unsafe {
-      (self.lib.easy_impersonate)(
+      impersonate(
         self.handle,
         target_cstr.as_ptr(),
         if use_default_headers { 1 } else { 0 },
@@ -311,8 +723,8 @@ impl Curl {
   /// 获取错误信息字符串
   #[napi]
   pub fn error(&self, code: i32) -> String {
-    log_info!("Curl", "error {}", code);
-    curl_easy_error(code)
+    log_info!("Curl", handle = self.id(); "error {}", code);
+    easy_strerror(self.lib, code)
   }
 
   /// 获取curlID
@@ -321,10 +733,15 @@ impl Curl {
     get_ptr_address(self.handle)
   }
 
+  /// 创建该 Curl 时使用的库
+  pub(crate) fn lib(&self) -> &'static CurlFunctions {
+    self.lib
+  }
+
   /// 获取 curl handle（内部使用）- 添加安全检查
   pub fn get_handle(&self) -> CurlHandle {
     if self.handle.is_null() {
-      println!("Warning: curl handle is null!");
+      log_warn!("Curl", "curl handle is null");
     }
     self.handle
   }
@@ -340,7 +757,7 @@ impl Curl {
       return;
     }
 
-    log_info!("Curl", "easy_cleanup {:?}", self.id());
+    log_info!("Curl", handle = self.id(); "easy_cleanup");
     unsafe {
       // 释放 header 链表
       if let Some(list) = *self.req_header.get() {
@@ -349,8 +766,21 @@ impl Curl {
       }
       // 清空 body 数据
       (*self.req_body.get()).clear();
+      self.control.set_body(None);
+      *self.body_stream.get() = None;
+      *self.upload_stream.get() = None;
+      // WebSocket 的监听线程可能仍在收发
+      if let Some(websocket) = (*self.websocket.get()).take() {
+        websocket.release();
+      }
 
       (self.lib.easy_cleanup)(self.handle);
+      self.free_opt_lists();
+      *self.mime.get() = None;
+      *self.share.get() = None;
+      *self.curlu.get() = None;
+      *self.trace.get() = None;
+      *self.har.get() = None;
     }
   }
 
@@ -358,38 +788,64 @@ impl Curl {
   #[napi]
   pub fn reset(&self) -> Result<()> {
     self.check_close()?;
-    log_info!("Curl", "easy_reset");
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "easy_reset");
     unsafe {
       (*self.header_buffer.get()).clear();
       (*self.content_buffer.get()).clear();
 
       (self.lib.easy_reset)(self.handle);
     }
+    // reset 后选项不再引用这些链表
+    self.free_opt_lists();
+    self.control.set_body(None);
+    unsafe {
+      (*self.req_body.get()).clear();
+      *self.body_stream.get() = None;
+      *self.upload_stream.get() = None;
+      *self.trace.get() = None;
+      *self.har.get() = None;
+      *self.verbose.get() = false;
+    }
     Ok(())
   }
 
   /// 执行 curl 请求
   #[napi]
   pub fn perform_sync(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    // 同步执行会阻塞 JS 线程，流式回调无法被消费
+    if self.is_streaming() {
+      return Err(Error::from_reason(
+        "performSync does not support streaming, use perform instead",
+      ));
+    }
     // 确保数据回调已初始化
     self.init();
-    log_info!("Curl", "perform");
-    self.result(unsafe { (self.lib.easy_perform)(self.handle) })
+    log_info!("Curl", handle = self.id(); "perform");
+    let code = unsafe { (self.lib.easy_perform)(self.handle) };
+    self.record_har(code, None);
+    self.result(code)
   }
   #[napi]
   pub async fn perform_old(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
     // 确保数据回调已初始化
     self.init();
-    log_info!("Curl", "perform");
+    log_info!("Curl", handle = self.id(); "perform");
     // 为了满足 Send 约束，跨线程仅传递整数句柄值
     let handle = self.handle as usize;
+    let control = self.control.clone();
+    let lib = self.lib;
+    control.performing.store(true, Ordering::SeqCst);
     tokio::task::spawn_blocking(move || {
       unsafe {
-        // 恢复 lib 的引用
-        let lib = napi_load_library()?;
         let code = (lib.easy_perform)(handle as CurlHandle);
+        control.performing.store(false, Ordering::SeqCst);
         if code != 0 {
-          let error = curl_easy_error(code);
+          let error = easy_strerror(lib, code);
           return Err(Error::from_reason(format!(
             "failed with code: {} message:{}",
             code, error
@@ -404,12 +860,38 @@ impl Curl {
 
   /// 执行 curl 请求（使用 AsyncTask）
   #[napi]
-  pub fn perform(&self) -> Result<AsyncTask<PerformTask>> {
+  pub fn perform(&self, this: Reference<Curl>) -> Result<AsyncTask<PerformTask>> {
+    self.perform_task(this)
+  }
+
+  /// curl 为指向自身的引用
+  pub(crate) fn perform_task(&self, curl: Reference<Curl>) -> Result<AsyncTask<PerformTask>> {
+    // init 会清空缓冲区并重设回调，必须在确认空闲之后
+    self.check_close()?;
+    self.check_idle()?;
     // 确保数据回调已初始化
     self.init();
-    log_info!("Curl", "perform (AsyncTask)");
-    let handle = self.handle as usize;
-    Ok(AsyncTask::new(PerformTask { handle }))
+    log_info!("Curl", handle = self.id(); "perform (AsyncTask)");
+    self.control.performing.store(true, Ordering::SeqCst);
+    Ok(AsyncTask::new(PerformTask {
+      handle: self.handle as usize,
+      lib: self.lib,
+      curl,
+      control: self.control.clone(),
+      code: 0,
+    }))
+  }
+
+  /// 交还 easy handle 的所有权，之后 close 不再清理它
+  pub(crate) fn release_handle(&mut self) {
+    self.handle = std::ptr::null_mut();
+  }
+
+  /// 由 CurlWebSocket 登记，替换时旧的 WebSocket 不再收发
+  pub(crate) fn set_websocket(&self, websocket: Arc<WsShared>) {
+    if let Some(old) = unsafe { (*self.websocket.get()).replace(websocket) } {
+      old.release();
+    }
   }
 
   /// 获取响应头数据
@@ -424,71 +906,525 @@ impl Curl {
     unsafe { Buffer::from((*self.content_buffer.get()).clone()) }
   }
 
-  /// 获取信息数组
+  fn is_streaming(&self) -> bool {
+    unsafe {
+      (*self.body_stream.get()).is_some()
+        || (*self.upload_stream.get()).is_some()
+        || (*self.mime.get()).as_ref().is_some_and(|mime| mime.has_streams())
+    }
+  }
+
+  pub fn control(&self) -> Arc<TransferControl> {
+    self.control.clone()
+  }
+
+  /// 通过 CURLOPT_MIMEPOST 发送 multipart 表单，表单随 Curl 一起释放
   #[napi]
-  pub fn get_info_list(&self, option: CurlInfo) -> Result<Vec<String>> {
+  pub fn set_mime(&self, mime: &CurlMime) -> Result<()> {
     self.check_close()?;
-    log_info!("Curl", "get_info_list {:?}", option);
-    let mut cookie_list: CurlSlist = std::ptr::null_mut();
-    self.get_info(option, &mut cookie_list as *mut _ as *mut c_void)?;
-    let mut cookies = Vec::new();
-    if !cookie_list.is_null() {
-      unsafe {
-        // 将指针转换为正确的结构体类型
-        let mut current = cookie_list as *mut CurlSlistNode;
-
-        // 遍历链表 - 就像 C 代码中的 while(each) 循环
-        while !current.is_null() {
-          let node = &*current;
-
-          // 检查 data 指针是否有效
-          if !node.data.is_null() {
-            // 将 C 字符串转换为 Rust 字符串
-            let cstr = std::ffi::CStr::from_ptr(node.data);
-            if let Ok(cookie_str) = cstr.to_str() {
-              cookies.push(cookie_str.to_string());
-            }
-          }
-
-          // 移动到下一个节点 - 相当于 C 代码中的 each = each->next
-          current = node.next;
-        }
+    self.check_idle()?;
+    mime.check_detached()?;
+    let data = mime.data();
+    if data.easy() != self.handle {
+      return Err(Error::from_reason("Mime was created for another Curl"));
+    }
+    self.set_opt(CurlOpt::MimePost, data.handle() as *const c_void)?;
+    unsafe {
+      *self.mime.get() = Some(data);
+    }
+    Ok(())
+  }
 
-        // 释放 cookie 列表 - 相当于 C 代码中的 curl_slist_free_all(cookies)
-        (self.lib.slist_free_all)(cookie_list);
+  /// 通过 CURLOPT_CURLU 使用 CurlUrl 作为请求地址，优先于 CurlOpt.Url
+  #[napi]
+  pub fn set_curl_url(&self, url: &CurlUrl) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    let data = url.data();
+    check_same_library(self.lib, data.lib())?;
+    self.set_opt(CurlOpt::CurlU, data.handle() as *const c_void)?;
+    unsafe {
+      *self.curlu.get() = Some(data);
+    }
+    Ok(())
+  }
+
+  /// 停止使用 CurlUrl
+  #[napi]
+  pub fn clear_curl_url(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    self.set_opt(CurlOpt::CurlU, std::ptr::null())?;
+    unsafe {
+      *self.curlu.get() = None;
+    }
+    Ok(())
+  }
+
+  /// 使用共享句柄，共享句柄在 Curl 释放前不会被清理
+  #[napi]
+  pub fn set_share(&self, share: &CurlShare) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    let data = share.data();
+    check_same_library(self.lib, data.lib())?;
+    self.set_opt(CurlOpt::Share, data.handle() as *const c_void)?;
+    unsafe {
+      *self.share.get() = Some(data);
+    }
+    Ok(())
+  }
+
+  /// 停止使用共享句柄
+  #[napi]
+  pub fn clear_share(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    self.set_opt(CurlOpt::Share, std::ptr::null())?;
+    unsafe {
+      *self.share.get() = None;
+    }
+    Ok(())
+  }
+
+  /// 移除 multipart 表单
+  #[napi]
+  pub fn clear_mime(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    self.set_opt(CurlOpt::MimePost, std::ptr::null())?;
+    unsafe {
+      *self.mime.get() = None;
+    }
+    Ok(())
+  }
+
+  /// 解析响应头，每次重定向、1xx 或 CONNECT 响应各为一跳，最后一项为最终响应
+  #[napi]
+  pub fn get_response_hops(&self) -> Vec<ResponseHop> {
+    unsafe { parse_response_hops(&*self.header_buffer.get()) }
+  }
+
+  /// 启用流式响应体：每个数据块通过回调投递，不再缓存到 get_resp_body
+  ///
+  /// 传输结束时回调收到 null，失败时收到错误，均在 perform 等 Promise 结算之前投递；
+  /// 未调用 consume_body 确认的字节数超过 high_water_mark 时传输会被暂停
+  #[napi]
+  pub fn set_body_stream(
+    &self,
+    callback: Arc<BodyCallback>,
+    high_water_mark: Option<u32>,
+  ) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "set_body_stream {:?}", high_water_mark);
+    let high_water_mark = high_water_mark
+      .map(|v| v as usize)
+      .unwrap_or(DEFAULT_HIGH_WATER_MARK)
+      .max(1);
+    self.control.set_body(Some(callback.clone()));
+    unsafe {
+      *self.body_stream.get() = Some(Box::new(BodyStream::new(
+        self.control.clone(),
+        callback,
+        high_water_mark,
+      )));
+    }
+    Ok(())
+  }
+
+  /// 关闭流式响应体，恢复为缓存模式
+  #[napi]
+  pub fn clear_body_stream(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    self.control.set_body(None);
+    unsafe {
+      *self.body_stream.get() = None;
+    }
+    Ok(())
+  }
+
+  /// 确认已消费的响应体字节数，必要时恢复被暂停的传输
+  #[napi]
+  pub fn consume_body(&self, bytes: u32) -> Result<()> {
+    self.check_close()?;
+    let Some(stream) = (unsafe { &*self.body_stream.get() }) else {
+      return Err(Error::from_reason("Body stream is not enabled"));
+    };
+    stream.consume(bytes as usize);
+    Ok(())
+  }
+
+  /// 启用流式请求体，数据通过 write_upload 写入、end_upload 结束
+  ///
+  /// 以 POST 发送，其他方法可通过 CURLOPT_CUSTOMREQUEST 指定；size 未知时使用 chunked 编码；
+  /// write_upload 返回 false 时应等待 on_drain 回调再继续写入
+  #[napi]
+  pub fn set_upload_stream(
+    &self,
+    size: Option<i64>,
+    on_drain: Option<Arc<ThreadsafeFunction<()>>>,
+    high_water_mark: Option<u32>,
+  ) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "set_upload_stream size: {:?}", size);
+    let high_water_mark = high_water_mark
+      .map(|v| v as usize)
+      .unwrap_or(DEFAULT_HIGH_WATER_MARK)
+      .max(1);
+    unsafe {
+      *self.upload_stream.get() = Some(Box::new(UploadStream::from_queue(
+        self.control.clone(),
+        size,
+        high_water_mark,
+        on_drain,
+      )));
+    }
+    Ok(())
+  }
+
+  /// 从文件流式上传请求体
+  #[napi]
+  pub fn set_upload_file(&self, path: String) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "set_upload_file {}", path);
+    let file = std::fs::File::open(&path)
+      .map_err(|e| Error::from_reason(format!("Failed to open {}: {}", path, e)))?;
+    let size = file
+      .metadata()
+      .map_err(|e| Error::from_reason(format!("Failed to stat {}: {}", path, e)))?
+      .len() as i64;
+    unsafe {
+      *self.upload_stream.get() = Some(Box::new(UploadStream::from_file(
+        self.control.clone(),
+        file,
+        size,
+      )));
+    }
+    Ok(())
+  }
+
+  /// 写入一块请求体数据，返回 false 表示缓冲已满
+  #[napi]
+  pub fn write_upload(&self, chunk: Buffer) -> Result<bool> {
+    self.check_close()?;
+    let Some(upload) = (unsafe { &*self.upload_stream.get() }) else {
+      return Err(Error::from_reason("Upload stream is not enabled"));
+    };
+    upload.write(&chunk).map_err(Error::from_reason)
+  }
+
+  /// 结束流式请求体
+  #[napi]
+  pub fn end_upload(&self) -> Result<()> {
+    self.check_close()?;
+    let Some(upload) = (unsafe { &*self.upload_stream.get() }) else {
+      return Err(Error::from_reason("Upload stream is not enabled"));
+    };
+    upload.end().map_err(Error::from_reason)
+  }
+
+  /// 关闭流式请求体
+  #[napi]
+  pub fn clear_upload_stream(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    if unsafe { (*self.upload_stream.get()).is_none() } {
+      return Ok(());
+    }
+    // 回调数据即将释放，libcurl 不能再引用
+    self.clear_upload_options()?;
+    unsafe {
+      *self.upload_stream.get() = None;
+    }
+    // 恢复 setBody 设置的请求体
+    self.restore_body()
+  }
+
+  // 清除流式请求体的读回调与定位回调，并取消其设置的 POST
+  fn clear_upload_options(&self) -> Result<()> {
+    self.set_opt(CurlOpt::ReadFunction, std::ptr::null())?;
+    self.set_opt(CurlOpt::ReadData, std::ptr::null())?;
+    self.set_opt(CurlOpt::SeekFunction, std::ptr::null())?;
+    self.set_opt(CurlOpt::SeekData, std::ptr::null())?;
+    self.set_option(CurlOpt::Post, Either5::C(false))
+  }
+
+  // 将 PostFields 指向 setBody 保存的请求体
+  fn restore_body(&self) -> Result<()> {
+    let buf = unsafe { &*self.req_body.get() };
+    if buf.is_empty() {
+      return Ok(());
+    }
+    self.set_opt(CurlOpt::PostFields, buf.as_ptr() as *const c_void)?;
+    self.set_opt(CurlOpt::PostFieldSize, buf.len() as *const c_void)
+  }
+
+  /// 设置进度回调，回调返回 true 时中止传输（CurlError.AbortedByCallback）
+  ///
+  /// interval_ms 为两次回调的最小间隔，默认 100ms
+  #[napi]
+  pub fn on_progress(
+    &self,
+    callback: Arc<ProgressCallback>,
+    interval_ms: Option<u32>,
+  ) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "on_progress interval: {:?}", interval_ms);
+    let interval = Duration::from_millis(interval_ms.unwrap_or(100) as u64);
+    self.control.set_progress(Some(callback), interval);
+    Ok(())
+  }
+
+  /// 开启线路追踪，通过 CURLOPT_DEBUGFUNCTION 记录收发的文本、头部、数据与 TLS 原始字节
+  ///
+  /// 传入 callback 时逐条交给 JS，否则收集到内存中，传输结束后通过 getTrace 取出；
+  /// 会同时开启 CURLOPT_VERBOSE
+  #[napi(
+    ts_args_type = "callback?: ((err: Error | null, entry: TraceEntry) => any) | undefined | null, options?: TraceOptions | undefined | null"
+  )]
+  pub fn enable_trace(
+    &self,
+    callback: Option<Arc<TraceCallback>>,
+    options: Option<TraceOptions>,
+  ) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "enable_trace callback: {}", callback.is_some());
+    self.debug_state().set_output(true, callback, options);
+    self.sync_debug()
+  }
+
+  /// 关闭线路追踪并丢弃未取出的记录
+  #[napi]
+  pub fn disable_trace(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "disable_trace");
+    if let Some(state) = unsafe { &*self.trace.get() } {
+      state.set_output(false, None, None);
+    }
+    self.sync_debug()
+  }
+
+  /// 取出并清空已收集的追踪记录，未开启追踪或设置了回调时为空
+  #[napi]
+  pub fn get_trace(&self) -> Vec<TraceEntry> {
+    match unsafe { &*self.trace.get() } {
+      Some(state) => state.take(),
+      None => Vec::new(),
+    }
+  }
+
+  /// 将之后每次传输记录到 HAR，传空取消
+  ///
+  /// 会开启 CURLOPT_DEBUGFUNCTION 以获取实际发出的请求头与请求体
+  #[napi]
+  pub fn set_har_recorder(&self, recorder: Option<&HarRecorder>) -> Result<()> {
+    self.check_close()?;
+    self.check_idle()?;
+    log_info!("Curl", handle = self.id(); "set_har_recorder {}", recorder.is_some());
+    let store = recorder.map(|recorder| recorder.store());
+    self
+      .debug_state()
+      .set_capture(store.as_ref().map(|store| store.max_body_size()));
+    unsafe { *self.har.get() = store };
+    self.sync_debug()
+  }
+
+  /// 由 CurlMulti 的 HAR 记录器开启请求采集，已挂载记录器时保持不变
+  pub(crate) fn capture_requests(&self, store: &HarStore) -> Result<()> {
+    if unsafe { (*self.har.get()).is_some() } {
+      return Ok(());
+    }
+    self.debug_state().set_capture(Some(store.max_body_size()));
+    self.sync_debug()
+  }
+
+  /// 传输结束后关闭 capture_requests 开启的采集，已挂载记录器时保持不变
+  pub(crate) fn release_capture(&self) {
+    if unsafe { (*self.har.get()).is_some() } {
+      return;
+    }
+    if let Some(state) = unsafe { (*self.trace.get()).as_deref() } {
+      state.set_capture(None);
+      let _ = self.sync_debug();
+    }
+  }
+
+  /// 传输结束后记录 HAR 条目，优先使用自身的记录器，其次是 CurlMulti 的
+  pub(crate) fn record_har(&self, code: c_int, fallback: Option<&Arc<HarStore>>) {
+    let Some(store) = unsafe { (*self.har.get()).as_ref() }.or(fallback) else {
+      return;
+    };
+    unsafe {
+      store.record(HarTransfer {
+        lib: self.lib,
+        handle: self.handle,
+        code,
+        request: (*self.trace.get())
+          .as_ref()
+          .and_then(|state| state.take_request()),
+        headers: &*self.header_buffer.get(),
+        body: (*self.body_stream.get())
+          .is_none()
+          .then(|| (*self.content_buffer.get()).as_slice()),
+      });
+    }
+  }
+
+  // 追踪与 HAR 共用的 CURLOPT_DEBUGDATA，首次使用时创建
+  fn debug_state(&self) -> &TraceState {
+    unsafe { (*self.trace.get()).get_or_insert_with(|| Box::new(TraceState::new(self.lib))) }
+  }
+
+  // 按追踪与 HAR 采集是否开启设置 CURLOPT_DEBUGFUNCTION
+  fn sync_debug(&self) -> Result<()> {
+    let state = unsafe { &*self.trace.get() };
+    match state.as_deref().filter(|state| state.is_active()) {
+      Some(state) => {
+        self.set_opt(CurlOpt::DebugFunction, debug_callback as *const c_void)?;
+        self.set_opt(CurlOpt::DebugData, state as *const TraceState as *const c_void)?;
+        self.set_opt(CurlOpt::Verbose, 1 as c_long as *const c_void)
       }
+      None => self.clear_trace_options(),
     }
+  }
 
-    Ok(cookies)
+  fn is_tracing(&self) -> bool {
+    unsafe { &*self.trace.get() }
+      .as_deref()
+      .is_some_and(|state| state.is_active())
   }
-  /// 设置链表
+
+  /// 移除调试回调，VERBOSE 恢复为用户的设置
+  pub(crate) fn clear_trace_options(&self) -> Result<()> {
+    let verbose = unsafe { *self.verbose.get() };
+    self.set_opt(CurlOpt::Verbose, verbose as c_long as *const c_void)?;
+    self.set_opt(CurlOpt::DebugFunction, std::ptr::null())?;
+    self.set_opt(CurlOpt::DebugData, std::ptr::null())
+  }
+
+  /// 移除进度回调
   #[napi]
-  pub fn set_opt_list(&self, option: CurlOpt, arrays: Vec<String>) -> Result<()> {
+  pub fn clear_progress(&self) -> Result<()> {
     self.check_close()?;
-    log_info!("Curl", "set_opt_list {:?}", option);
-    let mut list_ptr: CurlSlist = std::ptr::null_mut();
+    self.check_idle()?;
+    self.control.set_progress(None, Duration::ZERO);
+    Ok(())
+  }
 
-    for item in arrays {
-      let item_cstr = std::ffi::CString::new(item)
-        .map_err(|_| Error::new(Status::InvalidArg, "Invalid cookie string"))?;
-      unsafe {
-        list_ptr = (self.lib.slist_append)(list_ptr, item_cstr.as_ptr());
+  // 传输在其他线程中执行时不能读取信息；手动驱动的 multi 在 JS 线程中执行，加入期间仍可读取
+  fn check_performing(&self) -> Result<()> {
+    if self.control.is_performing() {
+      return Err(Error::from_reason("Curl is performing"));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn check_idle(&self) -> Result<()> {
+    self.check_performing()?;
+    if self.control.is_attached() {
+      return Err(Error::from_reason("Curl is attached to a CurlMulti"));
+    }
+    Ok(())
+  }
+
+  /// 获取指定响应头
+  ///
+  /// origin 为 CurlHeaderOrigin 的按位组合，默认 Header；request 为请求序号（重定向的每一跳），默认 -1 即最后一个请求
+  #[napi]
+  pub fn get_header(
+    &self,
+    name: String,
+    index: Option<u32>,
+    origin: Option<u32>,
+    request: Option<i32>,
+  ) -> Result<Option<CurlHeaderEntry>> {
+    self.check_close()?;
+    let name_cstr = std::ffi::CString::new(name)
+      .map_err(|_| Error::new(Status::InvalidArg, "Invalid header name"))?;
+    let easy_header = self.lib.easy_header.get()?;
+    let mut header: *mut CurlHeader = std::ptr::null_mut();
+    let code = unsafe {
+      easy_header(
+        self.handle,
+        name_cstr.as_ptr(),
+        index.unwrap_or(0) as usize,
+        origin.unwrap_or(CurlHeaderOrigin::Header as u32),
+        request.unwrap_or(-1),
+        &mut header,
+      )
+    };
+    if code == CurlHeaderError::Missing as c_int || code == CurlHeaderError::NoHeaders as c_int {
+      return Ok(None);
+    }
+    if code != CurlHeaderError::Ok as c_int || header.is_null() {
+      return Err(Error::from_reason(format!(
+        "curl_easy_header failed with code: {}",
+        code
+      )));
+    }
+    Ok(Some(unsafe { CurlHeaderEntry::from_raw(&*header) }))
+  }
+
+  /// 按顺序获取某个请求的全部响应头，参数含义同 get_header
+  #[napi]
+  pub fn get_headers(
+    &self,
+    origin: Option<u32>,
+    request: Option<i32>,
+  ) -> Result<Vec<CurlHeaderEntry>> {
+    self.check_close()?;
+    let origin = origin.unwrap_or(CurlHeaderOrigin::Header as u32);
+    let request = request.unwrap_or(-1);
+    let mut headers = Vec::new();
+    let nextheader = self.lib.easy_nextheader.get()?;
+    let mut prev: *mut CurlHeader = std::ptr::null_mut();
+    loop {
+      let header = unsafe { nextheader(self.handle, origin, request, prev) };
+      if header.is_null() {
+        break;
       }
+      headers.push(unsafe { CurlHeaderEntry::from_raw(&*header) });
+      prev = header;
     }
+    Ok(headers)
+  }
 
-    if list_ptr.is_null() {
-      return Err(Error::new(
-        Status::GenericFailure,
-        "Failed to create cookie list",
-      ));
+  /// 获取信息数组，支持 SslEngines、CookieList
+  #[napi]
+  pub fn get_info_list(&self, option: CurlInfo) -> Result<Vec<String>> {
+    self.check_close()?;
+    log_info!("Curl", handle = self.id(); "get_info_list {:?}", option);
+    // 同为 0x400000 的 CertInfo、TlsSslPtr 并非 curl_slist
+    if !matches!(option, CurlInfo::SslEngines | CurlInfo::CookieList) {
+      return Err(info_type_error(option, "list"));
+    }
+    let mut list: CurlSlist = std::ptr::null_mut();
+    self.get_info(option, &mut list as *mut _ as *mut c_void)?;
+    if list.is_null() {
+      return Ok(Vec::new());
     }
-    self.set_opt(option, list_ptr as *const c_void)?;
-    // 释放链表
     unsafe {
-      (self.lib.slist_free_all)(list_ptr);
+      let strings = slist_strings(list as *const CurlSlistNode);
+      // 这两项返回的链表由调用方释放
+      (self.lib.slist_free_all)(list);
+      Ok(strings)
     }
+  }
 
-    Ok(())
+  /// 设置链表
+  #[napi]
+  pub fn set_opt_list(&self, option: CurlOpt, arrays: Vec<String>) -> Result<()> {
+    self.check_close()?;
+    log_info!("Curl", handle = self.id(); "set_opt_list {:?}", option);
+    // 链表需存活到传输结束，不能在 setopt 后立即释放
+    self.set_slist(option as c_int, arrays)
   }
 
   /// 获取cookie列表
@@ -513,6 +1449,16 @@ impl Curl {
   }
 }
 
+// Number.MAX_SAFE_INTEGER
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn info_type_error(option: CurlInfo, expected: &str) -> Error {
+  Error::new(
+    Status::InvalidArg,
+    format!("CurlInfo::{:?} cannot be read as {}", option, expected),
+  )
+}
+
 // 为了安全，实现 Drop trait 来确保资源正确清理
 impl Drop for Curl {
   fn drop(&mut self) {
@@ -1,19 +1,42 @@
 use std::{
+  cell::RefCell,
+  collections::HashMap,
   ffi::{c_int, c_long, c_void},
-  sync::{Arc, Mutex},
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex,
+  },
+  thread::JoinHandle,
 };
 
-use napi::{bindgen_prelude::*, threadsafe_function::ThreadsafeFunction};
+use napi::{bindgen_prelude::*, threadsafe_function::ThreadsafeFunction, JsDeferred};
 use napi_derive::napi;
 
 use crate::{
-  api::curl_multi_error,
-  constants::CurlMOpt,
+  api::{easy_strerror, multi_strerror},
+  constants::{CurlInfo, CurlMOpt, CurlOpt},
   curl::Curl,
-  loader::{napi_load_library, CurlFunctions, CurlHandle, CurlMultiHandle},
+  har::{HarRecorder, HarStore},
+  library::{check_same_library, resolve_library, CurlLibrary},
+  loader::{CurlFunctions, CurlHandle, CurlMultiHandle},
+  push::{push_callback, PushCallback, PushRegistry, PushedCallback},
+  stream::{TransferControl, Waker},
   utils::get_ptr_address,
+  uv::{get_data, napi_load_uv, UvFunctions, UvHandle, UvLoop, UvOsSock, UV_READABLE, UV_WRITABLE},
 };
-use crate::{loader::CurlWaitFd, log_info};
+use crate::{loader::CurlWaitFd, log_info, log_warn};
+
+// curl_multi_socket_action 使用的常量
+const CURL_SOCKET_TIMEOUT: c_int = -1;
+const CURL_POLL_IN: c_int = 1;
+const CURL_POLL_OUT: c_int = 2;
+const CURL_POLL_REMOVE: c_int = 4;
+const CURL_CSELECT_IN: c_int = 1;
+const CURL_CSELECT_OUT: c_int = 2;
+const CURL_CSELECT_ERR: c_int = 4;
+const CURLMSG_DONE: c_int = 1;
+// reactor 线程单次 multi_poll 的最长等待
+const REACTOR_POLL_MS: c_int = 1000;
 
 #[napi(object)]
 pub struct CurlMsgResult {
@@ -42,10 +65,253 @@ pub struct TimerData {
   pub timeout_ms: i64,
 }
 
-#[derive(Debug)]
 struct RawMulti {
   handle: CurlMultiHandle,
   lib: &'static CurlFunctions,
+  // 通过 CURLOPT_PRIVATE 关联到 easy handle 的传输 id -> 传输
+  transfers: Mutex<HashMap<usize, Transfer>>,
+  next_transfer: Arc<AtomicUsize>,
+  push: Arc<PushRegistry>,
+  // setHarRecorder 挂载的 HAR 记录器，记录经 addTransfer 完成的传输
+  har: Mutex<Option<Arc<HarStore>>>,
+  // 已加入 multi 的 easy handle，移出前 Curl 不能修改 libcurl 引用的数据
+  attached: Mutex<HashMap<usize, Arc<TransferControl>>>,
+  // reactor 运行时唤醒其线程，加入的句柄都交由该线程恢复
+  reactor_waker: Mutex<Option<Waker>>,
+}
+
+// RawMulti 只含 multi 句柄指针与静态函数表，且只经由已声明 Send/Sync 的 CurlMulti 访问，
+// 放进 Arc 只为保证句柄只清理一次；libcurl 允许 multi 句柄在线程间转移，只要不并发调用
+unsafe impl Send for RawMulti {}
+unsafe impl Sync for RawMulti {}
+
+impl RawMulti {
+  /// 初始化 Curl 并通过 CURLOPT_PRIVATE 关联传输 id
+  fn prepare_transfer(&self, curl: &Curl) -> Result<usize> {
+    curl.check_idle()?;
+    let id = self.next_transfer.fetch_add(1, Ordering::SeqCst) + 1;
+    curl.init();
+    curl.set_opt(CurlOpt::Private, id as *const c_void)?;
+    if let Some(store) = self.har_store() {
+      curl.capture_requests(&store)?;
+    }
+    self.attach(curl);
+    log_info!("CurlMulti", handle = curl.id(); "Adding transfer {}", id);
+    Ok(id)
+  }
+
+  /// 标记 Curl 已加入 multi
+  fn attach(&self, curl: &Curl) {
+    let control = curl.control();
+    control.set_attached(true);
+    if let Some(waker) = self.reactor_waker() {
+      control.set_reactor(Some(waker));
+    }
+    if let Ok(mut attached) = self.attached.lock() {
+      attached.insert(curl.get_handle() as usize, control);
+    }
+  }
+
+  /// easy handle 已移出 multi
+  fn detach(&self, easy: CurlHandle) {
+    if let Some(control) = self.untrack(easy) {
+      control.set_reactor(None);
+      control.set_attached(false);
+    }
+  }
+
+  // 从已加入的句柄中移除，传输的状态留待结算时在 JS 线程中清除
+  fn untrack(&self, easy: CurlHandle) -> Option<Arc<TransferControl>> {
+    self
+      .attached
+      .lock()
+      .ok()
+      .and_then(|mut attached| attached.remove(&(easy as usize)))
+  }
+
+  /// multi 关闭后所有 easy handle 都不再属于它
+  fn detach_all(&self) {
+    let attached = match self.attached.lock() {
+      Ok(mut attached) => std::mem::take(&mut *attached),
+      Err(_) => return,
+    };
+    for control in attached.into_values() {
+      control.set_reactor(None);
+      control.set_attached(false);
+    }
+  }
+
+  fn control(&self, easy: CurlHandle) -> Option<Arc<TransferControl>> {
+    self
+      .attached
+      .lock()
+      .ok()
+      .and_then(|attached| attached.get(&(easy as usize)).cloned())
+  }
+
+  fn reactor_waker(&self) -> Option<Waker> {
+    self.reactor_waker.lock().ok().and_then(|waker| waker.clone())
+  }
+
+  /// 设置 reactor 的唤醒函数并应用到已加入的句柄，决定流式传输的恢复方式
+  fn set_reactor(&self, waker: Option<Waker>) {
+    if let Ok(mut current) = self.reactor_waker.lock() {
+      *current = waker.clone();
+    }
+    if let Ok(attached) = self.attached.lock() {
+      for control in attached.values() {
+        control.set_reactor(waker.clone());
+      }
+    }
+  }
+
+  /// 在 reactor 线程中恢复被 JS 请求恢复的传输
+  fn poll_resumes(&self) {
+    let controls: Vec<_> = match self.attached.lock() {
+      Ok(attached) => attached.values().cloned().collect(),
+      Err(_) => return,
+    };
+    for control in controls {
+      control.poll_resume();
+    }
+  }
+
+  fn har_store(&self) -> Option<Arc<HarStore>> {
+    self.har.lock().ok().and_then(|har| har.clone())
+  }
+
+  /// 登记传输，完成时由 complete 结算
+  fn insert_transfer(&self, id: usize, transfer: Transfer) {
+    if let Ok(mut transfers) = self.transfers.lock() {
+      transfers.insert(id, transfer);
+    }
+  }
+
+  /// 读取 CURLINFO_PRIVATE 中的传输 id
+  fn transfer_id(&self, easy: CurlHandle) -> usize {
+    let mut id: *mut c_void = std::ptr::null_mut();
+    unsafe {
+      (self.lib.easy_getinfo)(
+        easy,
+        CurlInfo::Private as c_int,
+        &mut id as *mut _ as *mut c_void,
+      );
+    }
+    id as usize
+  }
+
+  /// 取出 easy handle 对应的传输
+  fn take_transfer(&self, easy: CurlHandle) -> Option<Transfer> {
+    let id = self.transfer_id(easy);
+    let transfer = self
+      .transfers
+      .lock()
+      .ok()
+      .and_then(|mut transfers| transfers.remove(&id))?;
+    self.untrack(easy);
+    Some(transfer)
+  }
+
+  fn is_transferring(&self, curl: &Curl) -> bool {
+    self.transfers.lock().is_ok_and(|transfers| {
+      transfers
+        .values()
+        .any(|transfer| transfer.curl.get_handle() == curl.get_handle())
+    })
+  }
+
+  fn has_transfers(&self) -> bool {
+    self
+      .transfers
+      .lock()
+      .is_ok_and(|transfers| !transfers.is_empty())
+      || self.push.has_pushes()
+  }
+
+  /// 处理 CURLMSG_DONE：移出 multi 并结算 Promise
+  fn complete(&self, easy: CurlHandle, code: c_int) {
+    // 流式响应体的结束信号先于 Promise 结算投递
+    if let Some(control) = self.control(easy) {
+      control.end_body(code);
+    }
+    // 服务器推送的传输由 PushRegistry 处理，其余不是 addTransfer 发起的传输保持原样
+    let Some(transfer) = self.take_transfer(easy) else {
+      self.push.complete(self.transfer_id(easy), code);
+      return;
+    };
+    unsafe { (self.lib.multi_remove_handle)(self.handle, easy) };
+    log_info!(
+      "CurlMulti",
+      "Transfer {} done with code: {}",
+      get_ptr_address(easy),
+      code
+    );
+    let lib = self.lib;
+    transfer.settle(Some((code, self.har_store())), move |env, curl| {
+      if code == 0 {
+        return Ok(curl);
+      }
+      Err(curl_error(&env, lib, code)?)
+    });
+  }
+
+  /// 以 reason 拒绝 easy handle 对应的传输
+  fn fail(&self, easy: CurlHandle, reason: String) {
+    if let Some(control) = self.control(easy) {
+      control.fail_body(&reason);
+    }
+    if let Some(transfer) = self.take_transfer(easy) {
+      transfer.settle(None, move |_, _| Err(Error::from_reason(reason)));
+    }
+    // addHandle 加入的句柄没有对应的传输
+    self.detach(easy);
+  }
+
+  /// 读取全部完成消息并结算传输
+  fn drain_info(&self) {
+    self.push.cancel_denied();
+    loop {
+      let mut msgs_left = 0;
+      let msg_ptr = unsafe { (self.lib.multi_info_read)(self.handle, &mut msgs_left) };
+      if msg_ptr.is_null() {
+        break;
+      }
+      // remove_handle 之后消息失效，先取出字段
+      let (msg, easy, code) = unsafe {
+        let msg = &*msg_ptr;
+        (msg.msg, msg.easy_handle, msg.data.result)
+      };
+      if msg == CURLMSG_DONE {
+        self.complete(easy, code);
+      }
+    }
+  }
+
+  /// 拒绝所有未完成的传输
+  fn reject_transfers(&self, reason: &str) {
+    let transfers = match self.transfers.lock() {
+      Ok(mut transfers) => std::mem::take(&mut *transfers),
+      Err(_) => return,
+    };
+    for transfer in transfers.into_values() {
+      unsafe { (self.lib.multi_remove_handle)(self.handle, transfer.curl.get_handle()) };
+      transfer.curl.control().fail_body(reason);
+      self.untrack(transfer.curl.get_handle());
+      let reason = reason.to_string();
+      transfer.settle(None, move |_, _| Err(Error::from_reason(reason)));
+    }
+  }
+}
+
+/// 构造携带 CurlError 代码的 JS Error，code 属性为错误码
+fn curl_error(env: &Env, lib: &'static CurlFunctions, code: c_int) -> Result<Error> {
+  let mut error = env.create_error(Error::from_reason(format!(
+    "failed with code: {} message:{}",
+    code,
+    easy_strerror(lib, code)
+  )))?;
+  error.set_named_property("code", code)?;
+  Ok(Error::from(error.to_unknown()))
 }
 
 struct MultiData {
@@ -57,6 +323,7 @@ struct MultiData {
 // 使用 AsyncTask 封装 multi_poll 的阻塞调用
 pub struct MultiPollTask {
   handle: usize,
+  lib: &'static CurlFunctions,
   timeout_ms: i32,
 }
 
@@ -68,8 +335,7 @@ impl napi::Task for MultiPollTask {
     let mut remaining = 0;
     unsafe {
       let extra_fds: *mut CurlWaitFd = std::ptr::null_mut();
-      let lib = napi_load_library()?;
-      let code = (lib.multi_poll)(
+      let code = (self.lib.multi_poll)(
         self.handle as CurlMultiHandle,
         extra_fds,
         0,
@@ -80,7 +346,7 @@ impl napi::Task for MultiPollTask {
         return Err(Error::from_reason(format!(
           "failed with code: {} message: {}",
           code,
-          curl_multi_error(code)
+          multi_strerror(self.lib, code)
         )));
       }
     }
@@ -99,6 +365,7 @@ impl napi::Task for MultiPollTask {
 // 使用 AsyncTask 封装 multi_wait 的阻塞调用
 pub struct MultiWaitTask {
   handle: usize,
+  lib: &'static CurlFunctions,
   timeout_ms: i32,
 }
 
@@ -110,8 +377,7 @@ impl napi::Task for MultiWaitTask {
     let mut remaining = 0;
     unsafe {
       let extra_fds: *mut CurlWaitFd = std::ptr::null_mut();
-      let lib = napi_load_library()?;
-      let code = (lib.multi_wait)(
+      let code = (self.lib.multi_wait)(
         self.handle as CurlMultiHandle,
         extra_fds,
         0,
@@ -122,7 +388,7 @@ impl napi::Task for MultiWaitTask {
         return Err(Error::from_reason(format!(
           "failed with code: {} message: {}",
           code,
-          curl_multi_error(code)
+          multi_strerror(self.lib, code)
         )));
       }
     }
@@ -138,6 +404,379 @@ impl napi::Task for MultiWaitTask {
   }
 }
 
+type TransferResolver = Box<dyn FnOnce(Env) -> Result<Reference<Curl>>>;
+type TransferDeferred = JsDeferred<Reference<Curl>, TransferResolver>;
+
+// 由 addTransfer/fetch 发起的传输
+struct Transfer {
+  // 持有 JS 端的 Curl，传输结束前不会被回收，完成时作为 Promise 的结果
+  curl: Reference<Curl>,
+  deferred: TransferDeferred,
+}
+
+impl Transfer {
+  /// 在 JS 线程中结算 Promise；Reference 随 resolver 回到 JS 线程再释放
+  ///
+  /// record 为完成时的结果与 multi 的 HAR 记录器；HAR、请求采集与传输状态都只在 JS 线程中修改
+  fn settle(
+    self,
+    record: Option<(c_int, Option<Arc<HarStore>>)>,
+    resolver: impl FnOnce(Env, Reference<Curl>) -> Result<Reference<Curl>> + 'static,
+  ) {
+    let curl = self.curl;
+    self.deferred.resolve(Box::new(move |env| {
+      if let Some((code, har)) = record {
+        curl.record_har(code, har.as_ref());
+      }
+      curl.release_capture();
+      let control = curl.control();
+      control.set_reactor(None);
+      control.set_attached(false);
+      resolver(env, curl)
+    }));
+  }
+}
+
+// 后台 reactor 线程的指令，reactor 运行时 multi handle 只在该线程中操作
+enum ReactorCommand {
+  Add(usize),
+  Remove(usize),
+}
+
+struct ReactorShared {
+  raw: Arc<RawMulti>,
+  // 线程因 multi 出错退出后记录原因，之后不再接受指令
+  commands: Mutex<(Vec<ReactorCommand>, Option<String>)>,
+  stopped: AtomicBool,
+}
+
+// 后台 reactor：独占一个线程循环 multi_poll，通过 multi_wakeup 唤醒
+struct Reactor {
+  shared: Arc<ReactorShared>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl Reactor {
+  fn start(raw: Arc<RawMulti>) -> Self {
+    let shared = Arc::new(ReactorShared {
+      raw,
+      commands: Mutex::new((Vec::new(), None)),
+      stopped: AtomicBool::new(false),
+    });
+    let thread_shared = shared.clone();
+    let thread = std::thread::spawn(move || thread_shared.run());
+    Reactor {
+      shared,
+      thread: Some(thread),
+    }
+  }
+
+  fn send(&self, command: ReactorCommand) -> Result<()> {
+    if let Ok(mut commands) = self.shared.commands.lock() {
+      if let Some(reason) = &commands.1 {
+        return Err(Error::from_reason(reason.clone()));
+      }
+      commands.0.push(command);
+    }
+    self.shared.wakeup();
+    Ok(())
+  }
+
+  /// 线程出错退出的原因
+  fn failure(&self) -> Option<String> {
+    self
+      .shared
+      .commands
+      .lock()
+      .ok()
+      .and_then(|commands| commands.1.clone())
+  }
+
+  /// 停止并等待线程退出，之后 multi handle 可以在 JS 线程中使用
+  fn stop(mut self) {
+    self.shared.stopped.store(true, Ordering::SeqCst);
+    self.shared.wakeup();
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+    // 线程退出前未处理的指令
+    let commands = self.shared.take_commands();
+    self.shared.apply(commands);
+  }
+}
+
+impl ReactorShared {
+  fn wakeup(&self) {
+    unsafe { (self.raw.lib.multi_wakeup)(self.raw.handle) };
+  }
+
+  fn take_commands(&self) -> Vec<ReactorCommand> {
+    self
+      .commands
+      .lock()
+      .map(|mut commands| std::mem::take(&mut commands.0))
+      .unwrap_or_default()
+  }
+
+  // multi 出错后线程无法继续驱动：拒绝后续指令并结束所有传输
+  fn abort(&self, reason: String) {
+    log_warn!("CurlMulti", "Reactor aborted: {}", reason);
+    self.stopped.store(true, Ordering::SeqCst);
+    let commands = match self.commands.lock() {
+      Ok(mut commands) => {
+        commands.1 = Some(reason.clone());
+        std::mem::take(&mut commands.0)
+      }
+      Err(_) => Vec::new(),
+    };
+    for command in commands {
+      match command {
+        ReactorCommand::Add(easy) => self.raw.fail(easy as CurlHandle, reason.clone()),
+        command => self.apply(vec![command]),
+      }
+    }
+    self.raw.reject_transfers(&reason);
+  }
+
+  fn apply(&self, commands: Vec<ReactorCommand>) {
+    let raw = &self.raw;
+    for command in commands {
+      match command {
+        ReactorCommand::Add(easy) => {
+          let code = unsafe { (raw.lib.multi_add_handle)(raw.handle, easy as CurlHandle) };
+          if code != 0 {
+            raw.fail(
+              easy as CurlHandle,
+              format!(
+                "failed with code: {} message:{}",
+                code,
+                multi_strerror(raw.lib, code)
+              ),
+            );
+          }
+        }
+        ReactorCommand::Remove(easy) => {
+          unsafe { (raw.lib.multi_remove_handle)(raw.handle, easy as CurlHandle) };
+          raw.fail(easy as CurlHandle, "Transfer has been removed".to_string());
+        }
+      }
+    }
+  }
+
+  fn run(&self) {
+    log_info!("CurlMulti", "Reactor started");
+    let raw = &self.raw;
+    while !self.stopped.load(Ordering::SeqCst) {
+      let commands = self.take_commands();
+      self.apply(commands);
+      raw.poll_resumes();
+
+      let mut running = 0;
+      let code = unsafe { (raw.lib.multi_perform)(raw.handle, &mut running) };
+      raw.drain_info();
+      if code != 0 {
+        self.abort(format!(
+          "Reactor perform failed with code: {} message: {}",
+          code,
+          multi_strerror(raw.lib, code)
+        ));
+        break;
+      }
+
+      let mut numfds = 0;
+      let code = unsafe {
+        (raw.lib.multi_poll)(
+          raw.handle,
+          std::ptr::null_mut(),
+          0,
+          REACTOR_POLL_MS,
+          &mut numfds,
+        )
+      };
+      if code != 0 {
+        self.abort(format!(
+          "Reactor poll failed with code: {} message: {}",
+          code,
+          multi_strerror(raw.lib, code)
+        ));
+        break;
+      }
+    }
+    log_info!("CurlMulti", "Reactor stopped");
+  }
+}
+
+// uv_poll_t 的 data，记录所属驱动与 socket
+struct SocketPoll {
+  driver: *const UvDriver,
+  sockfd: c_int,
+}
+
+// 内置事件循环驱动：curl 的 socket 和 timer 直接注册到 Node 的 libuv 循环
+// 所有 uv 回调都在 JS 线程执行，因此只需 RefCell
+struct UvDriver {
+  raw: Arc<RawMulti>,
+  uv: &'static UvFunctions,
+  uv_loop: UvLoop,
+  timer: UvHandle,
+  polls: RefCell<HashMap<c_int, UvHandle>>,
+}
+
+impl UvDriver {
+  fn new(env: &Env, raw: Arc<RawMulti>) -> Result<Box<Self>> {
+    let uv = napi_load_uv()?;
+    let uv_loop = env.get_uv_event_loop()? as UvLoop;
+    let mut driver = Box::new(UvDriver {
+      raw,
+      uv,
+      uv_loop,
+      timer: std::ptr::null_mut(),
+      polls: RefCell::new(HashMap::new()),
+    });
+    let driver_ptr = &*driver as *const UvDriver as *mut c_void;
+    driver.timer = uv.alloc_timer(driver_ptr);
+    unsafe {
+      if (uv.timer_init)(uv_loop, driver.timer) != 0 {
+        uv.free_timer(driver.timer);
+        return Err(Error::from_reason("Failed to initialize uv timer"));
+      }
+      // 没有进行中的传输时不阻止 Node 退出
+      (uv.unref_handle)(driver.timer);
+
+      let lib = driver.raw.lib;
+      let handle = driver.raw.handle;
+      (lib.multi_setopt)(
+        handle,
+        CurlMOpt::SocketFunction as c_int,
+        uv_socket_callback as *const c_void,
+      );
+      (lib.multi_setopt)(handle, CurlMOpt::SocketData as c_int, driver_ptr);
+      (lib.multi_setopt)(
+        handle,
+        CurlMOpt::TimerFunction as c_int,
+        uv_timer_callback as *const c_void,
+      );
+      (lib.multi_setopt)(handle, CurlMOpt::TimerData as c_int, driver_ptr);
+    }
+    Ok(driver)
+  }
+
+  /// 响应 CURLMOPT_SOCKETFUNCTION，更新 socket 的 uv_poll 监听
+  fn watch(&self, sockfd: c_int, what: c_int) {
+    let mut polls = self.polls.borrow_mut();
+    if what == CURL_POLL_REMOVE {
+      if let Some(handle) = polls.remove(&sockfd) {
+        self.close_poll(handle);
+      }
+      return;
+    }
+
+    let handle = match polls.get(&sockfd) {
+      Some(handle) => *handle,
+      None => {
+        let context = Box::into_raw(Box::new(SocketPoll {
+          driver: self,
+          sockfd,
+        }));
+        let handle = self.uv.alloc_poll(context as *mut c_void);
+        let code = unsafe { (self.uv.poll_init_socket)(self.uv_loop, handle, sockfd as UvOsSock) };
+        if code != 0 {
+          log_warn!("CurlMulti", "uv_poll_init_socket failed: {}", code);
+          unsafe { drop(Box::from_raw(context)) };
+          self.uv.free_poll(handle);
+          return;
+        }
+        if !self.raw.has_transfers() {
+          unsafe { (self.uv.unref_handle)(handle) };
+        }
+        polls.insert(sockfd, handle);
+        handle
+      }
+    };
+
+    let mut events = 0;
+    if what & CURL_POLL_IN != 0 {
+      events |= UV_READABLE;
+    }
+    if what & CURL_POLL_OUT != 0 {
+      events |= UV_WRITABLE;
+    }
+    unsafe {
+      if events == 0 {
+        (self.uv.poll_stop)(handle);
+      } else {
+        (self.uv.poll_start)(handle, events, on_uv_poll);
+      }
+    }
+  }
+
+  /// 响应 CURLMOPT_TIMERFUNCTION，-1 表示删除定时器
+  fn schedule(&self, timeout_ms: c_long) {
+    unsafe {
+      if timeout_ms < 0 {
+        (self.uv.timer_stop)(self.timer);
+      } else {
+        (self.uv.timer_start)(self.timer, on_uv_timer, timeout_ms as u64, 0);
+      }
+    }
+  }
+
+  fn socket_action(&self, sockfd: c_int, flags: c_int) {
+    let mut running = 0;
+    let code =
+      unsafe { (self.raw.lib.multi_socket_action)(self.raw.handle, sockfd, flags, &mut running) };
+    if code != 0 {
+      log_warn!(
+        "CurlMulti",
+        "socket_action failed with code: {} message: {}",
+        code,
+        multi_strerror(self.raw.lib, code)
+      );
+    }
+    self.check_info();
+  }
+
+  fn check_info(&self) {
+    self.raw.drain_info();
+    self.update_ref();
+  }
+
+  /// 有进行中的传输时 uv 句柄保持事件循环存活
+  fn update_ref(&self) {
+    let active = self.raw.has_transfers();
+    let set_ref = if active {
+      &self.uv.ref_handle
+    } else {
+      &self.uv.unref_handle
+    };
+    unsafe {
+      set_ref(self.timer);
+      for handle in self.polls.borrow().values() {
+        set_ref(*handle);
+      }
+    }
+  }
+
+  fn close_poll(&self, handle: UvHandle) {
+    unsafe {
+      (self.uv.poll_stop)(handle);
+      (self.uv.close)(handle, on_uv_poll_close);
+    }
+  }
+
+  /// 关闭所有 uv 句柄
+  fn shutdown(&self) {
+    let polls = std::mem::take(&mut *self.polls.borrow_mut());
+    for handle in polls.into_values() {
+      self.close_poll(handle);
+    }
+    unsafe {
+      (self.uv.timer_stop)(self.timer);
+      (self.uv.close)(self.timer, on_uv_timer_close);
+    }
+  }
+}
+
 #[napi(js_name = "CurlMulti")]
 pub struct CurlMulti {
   pub closed: bool,
@@ -145,6 +784,9 @@ pub struct CurlMulti {
   data: Arc<Mutex<MultiData>>,
   socket_data_ptr: Option<*const Mutex<MultiData>>,
   timer_data_ptr: Option<*const Mutex<MultiData>>,
+  // fetch 首次调用时创建
+  driver: Option<Box<UvDriver>>,
+  reactor: Option<Reactor>,
 }
 
 // 手动实现 Send 和 Sync trait
@@ -153,17 +795,28 @@ unsafe impl Sync for CurlMulti {}
 
 #[napi]
 impl CurlMulti {
+  /// library 默认为全局库，只能添加来自同一个库的 Curl
   #[napi(constructor)]
-  pub fn new() -> Result<Self> {
-    let lib = napi_load_library()?;
+  pub fn new(library: Option<&CurlLibrary>) -> Result<Self> {
+    let lib = resolve_library(library)?;
     let handle = unsafe { (lib.multi_init)() };
 
     if handle.is_null() {
       return Err(Error::from_reason("Failed to initialize curl multi handle"));
     }
 
+    let next_transfer = Arc::new(AtomicUsize::new(0));
     let multi = Self {
-      raw: Arc::new(RawMulti { handle, lib }),
+      raw: Arc::new(RawMulti {
+        handle,
+        lib,
+        transfers: Mutex::new(HashMap::new()),
+        next_transfer: next_transfer.clone(),
+        push: Arc::new(PushRegistry::new(lib, handle, next_transfer)),
+        har: Mutex::new(None),
+        attached: Mutex::new(HashMap::new()),
+        reactor_waker: Mutex::new(None),
+      }),
       data: Arc::new(Mutex::new(MultiData {
         socket: None,
         timer: None,
@@ -171,6 +824,8 @@ impl CurlMulti {
       closed: false,
       socket_data_ptr: None,
       timer_data_ptr: None,
+      driver: None,
+      reactor: None,
     };
 
     // multi.setup_default_callbacks()?;
@@ -233,12 +888,132 @@ impl CurlMulti {
     Ok(())
   }
 
+  // 内置驱动接管了 socket/timer 回调
+  fn check_manual(&self) -> Result<()> {
+    self.check_reactor()?;
+    if self.driver.is_some() {
+      return Err(Error::from_reason(
+        "CurlMulti is driven by fetch, socket and timer callbacks are unavailable",
+      ));
+    }
+    Ok(())
+  }
+
+  // reactor 运行时 multi handle 归后台线程所有
+  fn check_reactor(&self) -> Result<()> {
+    if let Some(reactor) = &self.reactor {
+      return Err(Error::from_reason(match reactor.failure() {
+        Some(reason) => format!("{}, call stopReactor first", reason),
+        None => "CurlMulti is driven by the reactor thread, call stopReactor first".to_string(),
+      }));
+    }
+    Ok(())
+  }
+
+  /// 启动后台 reactor 线程循环 multi_poll 驱动传输，完成结果通过 addTransfer 的 Promise 返回
+  ///
+  /// 不支持服务器推送，已设置 setPushHandler 时需先 clearPushHandler
+  #[napi]
+  pub fn start_reactor(&mut self) -> Result<()> {
+    self.check_close()?;
+    if self.driver.is_some() {
+      return Err(Error::from_reason(
+        "CurlMulti is driven by fetch, reactor is unavailable",
+      ));
+    }
+    if self.reactor.is_some() {
+      return Ok(());
+    }
+    // 与 reactor 运行时拒绝 setPushHandler 对应，两种顺序都不允许
+    if self.raw.push.has_handlers() {
+      return Err(Error::from_reason(
+        "CurlMulti has a push handler, call clearPushHandler before startReactor",
+      ));
+    }
+    log_info!("CurlMulti", "Starting reactor");
+    // 流式传输改由 reactor 线程恢复，恢复请求通过 multi_wakeup 通知
+    let lib = self.raw.lib;
+    let handle = self.raw.handle as usize;
+    self.raw.set_reactor(Some(Arc::new(move || unsafe {
+      (lib.multi_wakeup)(handle as CurlMultiHandle);
+    })));
+    self.reactor = Some(Reactor::start(self.raw.clone()));
+    Ok(())
+  }
+
+  /// 停止后台 reactor 线程，未完成的传输保留在 multi 中
+  #[napi]
+  pub fn stop_reactor(&mut self) {
+    if let Some(reactor) = self.reactor.take() {
+      reactor.stop();
+      self.raw.set_reactor(None);
+    }
+  }
+
+  /// 处理 HTTP/2 服务器推送：handler 收到 PUSH_PROMISE 后返回 true 接受，否则取消该推送
+  ///
+  /// 已接受的推送完成后以新的 Curl 交给 onPushed
+  #[napi]
+  pub fn set_push_handler(
+    &self,
+    handler: Arc<PushCallback>,
+    on_pushed: Arc<PushedCallback>,
+  ) -> Result<()> {
+    self.check_close()?;
+    self.check_reactor()?;
+    log_info!("CurlMulti", "Setting push handler");
+    self.raw.push.set_handlers(Some((handler, on_pushed)));
+    unsafe {
+      (self.raw.lib.multi_setopt)(
+        self.raw.handle,
+        CurlMOpt::PushFunction as c_int,
+        push_callback as *const c_void,
+      );
+      (self.raw.lib.multi_setopt)(
+        self.raw.handle,
+        CurlMOpt::PushData as c_int,
+        Arc::as_ptr(&self.raw.push) as *const c_void,
+      );
+    }
+    Ok(())
+  }
+
+  /// 将之后经 addTransfer、fetch 完成的传输记录到 HAR，传空取消
+  ///
+  /// Curl 自身通过 setHarRecorder 挂载了记录器时记录到其自身的记录器
+  #[napi]
+  pub fn set_har_recorder(&self, recorder: Option<&HarRecorder>) -> Result<()> {
+    self.check_close()?;
+    log_info!("CurlMulti", "Setting HAR recorder: {}", recorder.is_some());
+    if let Ok(mut har) = self.raw.har.lock() {
+      *har = recorder.map(|recorder| recorder.store());
+    }
+    Ok(())
+  }
+
+  /// 移除推送处理函数，之后的推送都会被拒绝
+  #[napi]
+  pub fn clear_push_handler(&self) -> Result<()> {
+    self.check_close()?;
+    self.check_reactor()?;
+    unsafe {
+      (self.raw.lib.multi_setopt)(
+        self.raw.handle,
+        CurlMOpt::PushFunction as c_int,
+        std::ptr::null::<c_void>(),
+      );
+    }
+    self.raw.push.set_handlers(None);
+    Ok(())
+  }
+
   #[napi]
   pub fn set_socket_callback(
     &mut self,
     callback: Arc<ThreadsafeFunction<SocketData>>,
   ) -> Result<()> {
     self.check_close()?;
+    self.check_manual()?;
     log_info!("CurlMulti", "Setting socket callback");
     if let Ok(mut data) = self.data.lock() {
       data.socket = Some(callback.clone());
@@ -271,6 +1046,7 @@ impl CurlMulti {
   #[napi]
   pub fn set_timer_callback(&mut self, callback: Arc<ThreadsafeFunction<TimerData>>) -> Result<()> {
     self.check_close()?;
+    self.check_manual()?;
     log_info!("CurlMulti", "Setting timer callback");
     if let Ok(mut data) = self.data.lock() {
       data.timer = Some(callback.clone());
@@ -307,9 +1083,92 @@ impl CurlMulti {
     if handle.is_null() {
       return Err(Error::from_reason("Invalid curl handle"));
     }
+    check_same_library(self.raw.lib, curl.lib())?;
+    curl.check_idle()?;
     curl.init();
     log_info!("CurlMulti", "Adding handle: {}", get_ptr_address(handle));
-    unsafe { Ok((self.raw.lib.multi_add_handle)(self.raw.handle, handle)) }
+    self.raw.attach(curl);
+    if let Some(reactor) = &self.reactor {
+      if let Err(err) = reactor.send(ReactorCommand::Add(handle as usize)) {
+        self.raw.detach(handle);
+        return Err(err);
+      }
+      return Ok(0);
+    }
+    let code = unsafe { (self.raw.lib.multi_add_handle)(self.raw.handle, handle) };
+    if code != 0 {
+      self.raw.detach(handle);
+    }
+    Ok(code)
+  }
+
+  /// 添加 Curl，CURLMSG_DONE 时 Promise 以该 Curl 解决，失败时以带 code（CurlError）的 Error 拒绝
+  ///
+  /// 完成的句柄会自动移出 multi；手动驱动时需调用 infoRead 读取完成消息
+  #[napi(ts_return_type = "Promise<Curl>")]
+  pub fn add_transfer<'env>(&self, env: &'env Env, curl: Reference<Curl>) -> Result<Object<'env>> {
+    self.check_close()?;
+    if let Some(reason) = self.reactor.as_ref().and_then(Reactor::failure) {
+      return Err(Error::from_reason(reason));
+    }
+    curl.check_close()?;
+    if self.raw.is_transferring(&curl) {
+      return Err(Error::from_reason("Curl is already being transferred"));
+    }
+    check_same_library(self.raw.lib, curl.lib())?;
+    let handle = curl.get_handle();
+    let id = self.raw.prepare_transfer(&curl)?;
+    if self.reactor.is_none() {
+      let code = unsafe { (self.raw.lib.multi_add_handle)(self.raw.handle, handle) };
+      if code != 0 {
+        self.raw.detach(handle);
+      }
+      self.result(code)?;
+    }
+    let (deferred, promise) = match env.create_deferred() {
+      Ok(created) => created,
+      Err(err) => {
+        if self.reactor.is_none() {
+          unsafe { (self.raw.lib.multi_remove_handle)(self.raw.handle, handle) };
+        }
+        self.raw.detach(handle);
+        return Err(err);
+      }
+    };
+    self.raw.insert_transfer(id, Transfer { curl, deferred });
+    if let Some(reactor) = &self.reactor {
+      // reactor 在此期间退出时传输已被拒绝或仍未结算，统一按失败处理
+      if let Err(err) = reactor.send(ReactorCommand::Add(handle as usize)) {
+        self.raw.fail(handle, err.reason.clone());
+      }
+    }
+    if let Some(driver) = &self.driver {
+      driver.update_ref();
+    }
+    Ok(promise)
+  }
+
+  /// 同 addTransfer，并由内置的 libuv 事件循环驱动，无需 JS 端调用 perform/socketAction
+  #[napi(ts_return_type = "Promise<Curl>")]
+  pub fn fetch<'env>(&mut self, env: &'env Env, curl: Reference<Curl>) -> Result<Object<'env>> {
+    self.check_close()?;
+    self.check_reactor()?;
+    if self.driver.is_none() {
+      log_info!("CurlMulti", "Starting uv driver");
+      let driver = UvDriver::new(env, self.raw.clone())?;
+      // 被拒绝的推送在定时器触发的 socket_action 中取消；decide 与定时器都在 JS 线程执行
+      let driver_ptr = &*driver as *const UvDriver as usize;
+      self.raw.push.set_waker(Some(Box::new(move || unsafe {
+        (*(driver_ptr as *const UvDriver)).schedule(0)
+      })));
+      self.driver = Some(driver);
+      // 驱动替换了 JS 端的 socket/timer 回调
+      if let Ok(mut data) = self.data.lock() {
+        data.socket = None;
+        data.timer = None;
+      }
+    }
+    self.add_transfer(env, curl)
   }
 
   #[napi]
@@ -320,18 +1179,32 @@ impl CurlMulti {
       return Err(Error::from_reason("Invalid curl handle"));
     }
     log_info!("CurlMulti", "Removing handle: {}", get_ptr_address(handle));
-    unsafe { Ok((self.raw.lib.multi_remove_handle)(self.raw.handle, handle)) }
+    // reactor 已出错退出时直接在当前线程移除
+    if let Some(reactor) = &self.reactor {
+      if reactor
+        .send(ReactorCommand::Remove(handle as usize))
+        .is_ok()
+      {
+        return Ok(0);
+      }
+    }
+    let code = unsafe { (self.raw.lib.multi_remove_handle)(self.raw.handle, handle) };
+    self
+      .raw
+      .fail(handle, "Transfer has been removed".to_string());
+    Ok(code)
   }
 
   #[napi]
   pub fn error(&self, err: i64) -> String {
     log_info!("CurlMulti", "Getting error for code: {}", err);
-    curl_multi_error(err as i32)
+    multi_strerror(self.raw.lib, err as i32)
   }
 
   #[napi]
   pub fn perform(&self) -> Result<i32> {
     self.check_close()?;
+    self.check_reactor()?;
     let mut remaining = 0;
     log_info!("CurlMulti", "Performing multi operation");
     unsafe {
@@ -340,6 +1213,7 @@ impl CurlMulti {
         return Err(Error::from_reason(format!("Perform failed: {}", result)));
       }
     }
+    self.raw.push.cancel_denied();
     Ok(remaining)
   }
 
@@ -347,21 +1221,32 @@ impl CurlMulti {
   #[napi]
   pub fn poll(&self, timeout_ms: i32) -> Result<AsyncTask<MultiPollTask>> {
     self.check_close()?;
+    self.check_reactor()?;
     let handle = self.raw.handle as usize;
-    Ok(AsyncTask::new(MultiPollTask { handle, timeout_ms }))
+    Ok(AsyncTask::new(MultiPollTask {
+      handle,
+      lib: self.raw.lib,
+      timeout_ms,
+    }))
   }
 
   /// 使用 AsyncTask 的 wait
   #[napi]
   pub fn wait(&self, timeout_ms: i32) -> Result<AsyncTask<MultiWaitTask>> {
     self.check_close()?;
+    self.check_reactor()?;
     let handle = self.raw.handle as usize;
-    Ok(AsyncTask::new(MultiWaitTask { handle, timeout_ms }))
+    Ok(AsyncTask::new(MultiWaitTask {
+      handle,
+      lib: self.raw.lib,
+      timeout_ms,
+    }))
   }
 
   #[napi]
   pub fn get_running_handles(&self) -> Result<i32> {
     self.check_close()?;
+    self.check_reactor()?;
     let mut remaining = 0;
     log_info!("CurlMulti", "Getting running handles");
     unsafe {
@@ -379,6 +1264,7 @@ impl CurlMulti {
   #[napi]
   pub fn socket_action(&self, socket: i64, what: i64) -> Result<i32> {
     self.check_close()?;
+    self.check_reactor()?;
     let mut remaining = 0;
     log_info!(
       "CurlMulti",
@@ -397,16 +1283,20 @@ impl CurlMulti {
         return Err(Error::from_reason(format!("Action failed: {}", result)));
       }
     }
+    // 手动驱动时被拒绝的推送在此取消
+    self.raw.push.cancel_denied();
     Ok(remaining)
   }
 
   #[napi]
   pub fn info_read(&self) -> Result<Option<CurlMsgResult>> {
     self.check_close()?;
+    self.check_reactor()?;
     if self.raw.handle.is_null() {
       return Err(Error::from_reason("Curl multi handle is null"));
     }
     log_info!("CurlMulti", "Reading info from multi handle");
+    self.raw.push.cancel_denied();
 
     let mut msgs_left = 0;
     let msg_ptr = unsafe { (self.raw.lib.multi_info_read)(self.raw.handle, &mut msgs_left) };
@@ -439,15 +1329,20 @@ impl CurlMulti {
     //   unsafe { curl_msg.data.result }
     // );
 
-    Ok(Some(CurlMsgResult {
+    let result = CurlMsgResult {
       msg: curl_msg.msg as i64,
       easy_handle: curl_msg.easy_handle as i64,
       easy_id: get_ptr_address(curl_msg.easy_handle),
       data: CurlMsgDataResult {
         whatever: unsafe { curl_msg.data.whatever as i64 },
-        result: unsafe { curl_msg.data.result as i32 },
+        result: unsafe { curl_msg.data.result },
       },
-    }))
+    };
+    // addTransfer 发起的传输在此结算，消息仍照常返回
+    if curl_msg.msg == CURLMSG_DONE {
+      self.raw.complete(curl_msg.easy_handle, result.data.result);
+    }
+    Ok(Some(result))
   }
 
   #[napi]
@@ -462,27 +1357,43 @@ impl CurlMulti {
     );
     self.closed = true;
 
+    if let Some(reactor) = self.reactor.take() {
+      reactor.stop();
+      self.raw.set_reactor(None);
+    }
+    self.raw.reject_transfers("CurlMulti has been closed");
+    self.raw.detach_all();
+    self.raw.push.clear();
+    if let Some(driver) = self.driver.take() {
+      driver.shutdown();
+    }
+
     unsafe {
       (self.raw.lib.multi_setopt)(
         self.raw.handle,
         CurlMOpt::SocketFunction as c_int,
-        std::ptr::null() as *const c_void,
+        std::ptr::null::<c_void>(),
       );
       (self.raw.lib.multi_setopt)(
         self.raw.handle,
         CurlMOpt::SocketData as c_int,
-        std::ptr::null() as *const c_void,
+        std::ptr::null::<c_void>(),
       );
 
       (self.raw.lib.multi_setopt)(
         self.raw.handle,
         CurlMOpt::TimerFunction as c_int,
-        std::ptr::null() as *const c_void,
+        std::ptr::null::<c_void>(),
       );
       (self.raw.lib.multi_setopt)(
         self.raw.handle,
         CurlMOpt::TimerData as c_int,
-        std::ptr::null() as *const c_void,
+        std::ptr::null::<c_void>(),
+      );
+      (self.raw.lib.multi_setopt)(
+        self.raw.handle,
+        CurlMOpt::PushFunction as c_int,
+        std::ptr::null::<c_void>(),
       );
 
       (self.raw.lib.multi_cleanup)(self.raw.handle);
@@ -559,7 +1470,7 @@ extern "C" fn timer_callback(
       let _ = tsfn.call(
         Ok(TimerData {
           multi_id: get_ptr_address(_multi),
-          timeout_ms: timeout_ms as i64,
+          timeout_ms,
         }),
         napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
       );
@@ -573,3 +1484,66 @@ extern "C" fn timer_callback(
   std::mem::forget(data_arc);
   result
 }
+
+extern "C" fn uv_socket_callback(
+  _easy: CurlHandle,
+  sockfd: c_int,
+  what: c_int,
+  userptr: *mut c_void,
+  _socketp: *mut c_void,
+) -> c_int {
+  if !userptr.is_null() {
+    let driver = unsafe { &*(userptr as *const UvDriver) };
+    driver.watch(sockfd, what);
+  }
+  0
+}
+
+extern "C" fn uv_timer_callback(
+  _multi: CurlMultiHandle,
+  timeout_ms: c_long,
+  userptr: *mut c_void,
+) -> c_int {
+  if !userptr.is_null() {
+    let driver = unsafe { &*(userptr as *const UvDriver) };
+    driver.schedule(timeout_ms);
+  }
+  0
+}
+
+extern "C" fn on_uv_poll(handle: UvHandle, status: c_int, events: c_int) {
+  let (driver, sockfd) = unsafe {
+    let context = &*(get_data(handle) as *const SocketPoll);
+    (&*context.driver, context.sockfd)
+  };
+  let mut flags = 0;
+  if status < 0 {
+    flags |= CURL_CSELECT_ERR;
+  } else {
+    if events & UV_READABLE != 0 {
+      flags |= CURL_CSELECT_IN;
+    }
+    if events & UV_WRITABLE != 0 {
+      flags |= CURL_CSELECT_OUT;
+    }
+  }
+  driver.socket_action(sockfd, flags);
+}
+
+extern "C" fn on_uv_timer(handle: UvHandle) {
+  let driver = unsafe { &*(get_data(handle) as *const UvDriver) };
+  driver.socket_action(CURL_SOCKET_TIMEOUT, 0);
+}
+
+extern "C" fn on_uv_poll_close(handle: UvHandle) {
+  if let Ok(uv) = napi_load_uv() {
+    unsafe { drop(Box::from_raw(get_data(handle) as *mut SocketPoll)) };
+    uv.free_poll(handle);
+  }
+}
+
+extern "C" fn on_uv_timer_close(handle: UvHandle) {
+  if let Ok(uv) = napi_load_uv() {
+    uv.free_timer(handle);
+  }
+}
@@ -0,0 +1,520 @@
+//! 下载 libcurl-impersonate 发布包：校验 SHA-256 清单后原子解压到按版本区分的缓存目录
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Error, Result, Task};
+use napi_derive::napi;
+use sha2::{Digest, Sha256};
+
+use crate::libpath::{apply_lib_path, default_version, PlatformInfo};
+use crate::log_info;
+use crate::utils::default_lib_name;
+
+// 与发布包位于同一目录的校验清单，每行为 "<sha256>  <文件名>"
+const CHECKSUM_MANIFEST: &str = "SHA256SUMS";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[napi(object)]
+pub struct EnsureLibraryOptions {
+  /// 发布版本，如 v1.0.0；未指定时取环境变量 CURL_IMPERSONATE_VERSION，再其次为本包发布时验证过的内置版本
+  pub version: Option<String>,
+  /// 缓存目录，默认为当前目录下的 libs，解压到 <cacheDir>/<version>/<平台>
+  pub cache_dir: Option<String>,
+  /// 替代 GitHub releases 的下载地址前缀，按 <mirror>/<version>/<文件名> 拼接；也可以是本地目录
+  pub mirror: Option<String>,
+  /// 直接指定发布包的 URL 或本地路径，用于离线安装
+  pub source: Option<String>,
+  /// 校验清单的 URL 或本地路径，默认为发布包同目录下的 SHA256SUMS；发布没有清单时需改用 sha256
+  pub checksums: Option<String>,
+  /// 发布包的 SHA-256，指定后不再读取校验清单
+  pub sha256: Option<String>,
+}
+
+#[napi(object)]
+pub struct EnsuredLibrary {
+  /// 动态库路径
+  pub path: String,
+  /// 是否已设为全局库路径；全局库已从其他路径加载时为 false，可改用 CurlLibrary 加载
+  pub global: bool,
+  /// 解压目录
+  pub dir: String,
+  pub version: String,
+  /// 本次是否下载，命中缓存时为 false
+  pub downloaded: bool,
+}
+
+pub struct EnsureLibraryTask {
+  options: EnsureLibraryOptions,
+}
+
+impl Task for EnsureLibraryTask {
+  type Output = EnsuredLibrary;
+  type JsValue = EnsuredLibrary;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    ensure(&self.options).map_err(Error::from_reason)
+  }
+
+  fn resolve(&mut self, _env: Env, mut output: Self::Output) -> Result<Self::JsValue> {
+    output.global = apply_lib_path(output.path.clone());
+    Ok(output)
+  }
+}
+
+/// 确保当前平台的 libcurl-impersonate 已下载到缓存目录，并尝试设为全局库路径
+#[napi(ts_return_type = "Promise<EnsuredLibrary>")]
+pub fn ensure_library(options: Option<EnsureLibraryOptions>) -> AsyncTask<EnsureLibraryTask> {
+  AsyncTask::new(EnsureLibraryTask {
+    options: options.unwrap_or(EnsureLibraryOptions {
+      version: None,
+      cache_dir: None,
+      mirror: None,
+      source: None,
+      checksums: None,
+      sha256: None,
+    }),
+  })
+}
+
+fn ensure(options: &EnsureLibraryOptions) -> std::result::Result<EnsuredLibrary, String> {
+  let platform = PlatformInfo::detect();
+  let version = options.version.clone().unwrap_or_else(default_version);
+  let version_dir = Path::new(options.cache_dir.as_deref().unwrap_or("libs")).join(&version);
+  let target_dir = version_dir.join(platform.target());
+  let installed = |path: PathBuf, downloaded| EnsuredLibrary {
+    path: path.to_string_lossy().into_owned(),
+    global: false,
+    dir: target_dir.to_string_lossy().into_owned(),
+    version: version.clone(),
+    downloaded,
+  };
+
+  if let Some(path) = find_library(&target_dir) {
+    log_info!("Download", "Using cached library {}", path.display());
+    return Ok(installed(path, false));
+  }
+
+  let source = match &options.source {
+    Some(source) => source.clone(),
+    None => platform.get_download_url(options.mirror.as_deref(), &version, true),
+  };
+  let expected = match &options.sha256 {
+    Some(sha256) => {
+      let sha256 = sha256.trim().to_ascii_lowercase();
+      if !is_sha256(&sha256) {
+        return Err(format!("Invalid sha256 option: {}", sha256));
+      }
+      sha256
+    }
+    None => {
+      let manifest = match &options.checksums {
+        Some(checksums) => checksums.clone(),
+        None => sibling(&source, CHECKSUM_MANIFEST),
+      };
+      // 上游发布不一定附带校验清单，缺失时提示改用 sha256 或 checksums
+      let mut text = String::new();
+      open(&manifest)
+        .and_then(|mut reader| {
+          reader
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Failed to read {}: {}", manifest, e))
+        })
+        .map_err(|e| {
+          format!(
+            "Cannot verify {}: checksum manifest is unavailable ({}); pass the sha256 option or a checksums location",
+            file_name(&source),
+            e
+          )
+        })?;
+      lookup_checksum(&text, file_name(&source))
+        .ok_or_else(|| format!("{} has no checksum for {}", manifest, file_name(&source)))?
+    }
+  };
+
+  fs::create_dir_all(&version_dir)
+    .map_err(|e| format!("Failed to create {}: {}", version_dir.display(), e))?;
+  let id = uuid::Uuid::new_v4();
+  let archive = version_dir.join(format!(".{}.tar.gz", id));
+  let staging = version_dir.join(format!(".{}", id));
+  let result = install(&source, &expected, &archive, &staging, &target_dir);
+  let _ = fs::remove_file(&archive);
+  if result.is_err() {
+    let _ = fs::remove_dir_all(&staging);
+  }
+  result?;
+
+  let path = find_library(&target_dir)
+    .ok_or_else(|| format!("No libcurl found in {}", target_dir.display()))?;
+  log_info!(
+    "Download",
+    "Installed {} into {}",
+    source,
+    target_dir.display()
+  );
+  Ok(installed(path, true))
+}
+
+// 下载并校验后先解压到临时目录，再整体重命名到目标目录
+fn install(
+  source: &str,
+  expected: &str,
+  archive: &Path,
+  staging: &Path,
+  target_dir: &Path,
+) -> std::result::Result<(), String> {
+  log_info!("Download", "Downloading {}", source);
+  let actual = download(source, archive)?;
+  if actual != expected {
+    return Err(format!(
+      "Checksum mismatch for {}: expected {}, got {}",
+      source, expected, actual
+    ));
+  }
+
+  let file = File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+  tar::Archive::new(GzDecoder::new(file))
+    .unpack(staging)
+    .map_err(|e| format!("Failed to extract {}: {}", source, e))?;
+  if find_library(staging).is_none() {
+    return Err(format!(
+      "{} does not contain {}",
+      source,
+      default_lib_name().display()
+    ));
+  }
+
+  let mut replaced = false;
+  loop {
+    match fs::rename(staging, target_dir) {
+      Ok(()) => return Ok(()),
+      // 其他进程已先完成安装
+      Err(_) if find_library(target_dir).is_some() => {
+        let _ = fs::remove_dir_all(staging);
+        return Ok(());
+      }
+      // 不完整的目录（如解压后被手动删除了文件）先整体移开再替换，只替换一次
+      Err(_) if !replaced && target_dir.exists() => {
+        replaced = true;
+        remove_incomplete(target_dir, &staging.with_extension("stale"))?;
+      }
+      Err(e) => {
+        return Err(format!(
+          "Failed to move into {}: {}",
+          target_dir.display(),
+          e
+        ))
+      }
+    }
+  }
+}
+
+// 重命名是原子的：目录已被其他安装进程移走时视为成功
+fn remove_incomplete(target_dir: &Path, stale: &Path) -> std::result::Result<(), String> {
+  match fs::rename(target_dir, stale) {
+    Ok(()) => {
+      let _ = fs::remove_dir_all(stale);
+      Ok(())
+    }
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(format!("Failed to remove {}: {}", target_dir.display(), e)),
+  }
+}
+
+// 写入 path 并返回内容的 SHA-256
+fn download(source: &str, path: &Path) -> std::result::Result<String, String> {
+  let mut reader = open(source)?;
+  let mut file = File::create(path).map_err(|e| format!("Failed to create archive: {}", e))?;
+  let mut hasher = Sha256::new();
+  let mut buffer = vec![0u8; 64 * 1024];
+  loop {
+    let n = reader
+      .read(&mut buffer)
+      .map_err(|e| format!("Failed to download {}: {}", source, e))?;
+    if n == 0 {
+      break;
+    }
+    hasher.update(&buffer[..n]);
+    file
+      .write_all(&buffer[..n])
+      .map_err(|e| format!("Failed to write archive: {}", e))?;
+  }
+  Ok(
+    hasher
+      .finalize()
+      .iter()
+      .map(|b| format!("{:02x}", b))
+      .collect(),
+  )
+}
+
+// http(s) 地址通过网络读取，其余视为本地路径（可带 file:// 前缀）
+fn open(location: &str) -> std::result::Result<Box<dyn Read>, String> {
+  if location.starts_with("http://") || location.starts_with("https://") {
+    let config = ureq::Agent::config_builder()
+      .timeout_global(Some(DOWNLOAD_TIMEOUT))
+      .build();
+    let response = ureq::Agent::new_with_config(config)
+      .get(location)
+      .call()
+      .map_err(|e| format!("Failed to download {}: {}", location, e))?;
+    return Ok(Box::new(response.into_body().into_reader()));
+  }
+  let path = location.strip_prefix("file://").unwrap_or(location);
+  let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+  Ok(Box::new(file))
+}
+
+fn lookup_checksum(manifest: &str, name: &str) -> Option<String> {
+  manifest.lines().find_map(|line| {
+    let mut parts = line.split_whitespace();
+    let hash = parts.next()?;
+    // sha256sum 的二进制模式在文件名前加 *
+    let file = parts.next()?.trim_start_matches('*');
+    let matches = file_name(file) == name && is_sha256(hash);
+    matches.then(|| hash.to_ascii_lowercase())
+  })
+}
+
+fn is_sha256(hash: &str) -> bool {
+  hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn file_name(location: &str) -> &str {
+  location.rsplit(['/', '\\']).next().unwrap_or(location)
+}
+
+// 与 location 同一目录下名为 name 的文件
+fn sibling(location: &str, name: &str) -> String {
+  match location.rfind(['/', '\\']) {
+    Some(index) => format!("{}{}", &location[..=index], name),
+    None => name.to_string(),
+  }
+}
+
+// 先查找约定路径，再在解压目录中按文件名查找（发布包可能多一层目录或只带版本号后缀）
+fn find_library(dir: &Path) -> Option<PathBuf> {
+  let expected = dir.join(default_lib_name());
+  if expected.is_file() {
+    return Some(expected);
+  }
+  let name = default_lib_name()
+    .file_name()?
+    .to_string_lossy()
+    .into_owned();
+  search(dir, &name, 3)
+}
+
+fn search(dir: &Path, name: &str, depth: usize) -> Option<PathBuf> {
+  let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+    .ok()?
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .collect();
+  entries.sort();
+  let found = entries.iter().find(|path| {
+    path.is_file()
+      && path
+        .file_name()
+        .is_some_and(|file| file.to_string_lossy().starts_with(name))
+  });
+  if let Some(path) = found {
+    return Some(path.clone());
+  }
+  if depth == 0 {
+    return None;
+  }
+  entries
+    .iter()
+    .filter(|path| path.is_dir())
+    .find_map(|path| search(path, name, depth - 1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use flate2::{write::GzEncoder, Compression};
+
+  // 每个测试独立的临时目录
+  fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("curl-download-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  // 生成含动态库的发布包与 SHA256SUMS，返回发布包路径
+  fn write_release(dir: &Path) -> PathBuf {
+    let archive = dir.join("libcurl-impersonate-test.tar.gz");
+    let mut builder = tar::Builder::new(GzEncoder::new(
+      File::create(&archive).unwrap(),
+      Compression::default(),
+    ));
+    let content = b"not a real library";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+      .append_data(&mut header, default_lib_name(), &content[..])
+      .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let hash: String = Sha256::digest(fs::read(&archive).unwrap())
+      .iter()
+      .map(|b| format!("{:02x}", b))
+      .collect();
+    fs::write(
+      dir.join(CHECKSUM_MANIFEST),
+      format!(
+        "{}  other.tar.gz\n{} *{}\n",
+        "0".repeat(64),
+        hash,
+        file_name(&archive.to_string_lossy())
+      ),
+    )
+    .unwrap();
+    archive
+  }
+
+  fn options(dir: &Path, source: &Path) -> EnsureLibraryOptions {
+    EnsureLibraryOptions {
+      version: Some("v0.0.0-test".to_string()),
+      cache_dir: Some(dir.join("cache").to_string_lossy().into_owned()),
+      mirror: None,
+      source: Some(source.to_string_lossy().into_owned()),
+      checksums: None,
+      sha256: None,
+    }
+  }
+
+  #[test]
+  fn looks_up_checksum_by_file_name() {
+    let hash = "A".repeat(64);
+    let manifest = format!(
+      "{}  libcurl-x86_64-linux.tar.gz\nnot a checksum line\n{} *dist/libcurl-arm64-linux.tar.gz\n",
+      "b".repeat(64),
+      hash
+    );
+    assert_eq!(
+      lookup_checksum(&manifest, "libcurl-x86_64-linux.tar.gz"),
+      Some("b".repeat(64))
+    );
+    assert_eq!(
+      lookup_checksum(&manifest, "libcurl-arm64-linux.tar.gz"),
+      Some("a".repeat(64))
+    );
+    assert_eq!(lookup_checksum(&manifest, "libcurl.tar.gz"), None);
+    // 长度或字符不合法的哈希被忽略
+    assert_eq!(
+      lookup_checksum("abc  libcurl.tar.gz", "libcurl.tar.gz"),
+      None
+    );
+    assert_eq!(
+      lookup_checksum(
+        &format!("{}  libcurl.tar.gz", "g".repeat(64)),
+        "libcurl.tar.gz"
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn splits_locations() {
+    assert_eq!(
+      file_name("https://example.com/v1/libcurl.tar.gz"),
+      "libcurl.tar.gz"
+    );
+    assert_eq!(file_name("C:\\libs\\libcurl.tar.gz"), "libcurl.tar.gz");
+    assert_eq!(file_name("libcurl.tar.gz"), "libcurl.tar.gz");
+    assert_eq!(
+      sibling("https://example.com/v1/libcurl.tar.gz", CHECKSUM_MANIFEST),
+      "https://example.com/v1/SHA256SUMS"
+    );
+    assert_eq!(
+      sibling("C:\\libs\\libcurl.tar.gz", "SUMS"),
+      "C:\\libs\\SUMS"
+    );
+    assert_eq!(sibling("libcurl.tar.gz", "SUMS"), "SUMS");
+  }
+
+  #[test]
+  fn installs_from_local_source() {
+    let dir = temp_dir();
+    let source = write_release(&dir);
+
+    let installed = ensure(&options(&dir, &source)).unwrap();
+    assert!(installed.downloaded);
+    assert!(!installed.global);
+    assert!(Path::new(&installed.path).is_file());
+    assert!(installed.path.starts_with(&installed.dir));
+
+    let cached = ensure(&options(&dir, &source)).unwrap();
+    assert!(!cached.downloaded);
+    assert_eq!(cached.path, installed.path);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn replaces_incomplete_install() {
+    let dir = temp_dir();
+    let source = write_release(&dir);
+    let options = options(&dir, &source);
+    let target_dir = dir
+      .join("cache")
+      .join("v0.0.0-test")
+      .join(PlatformInfo::detect().target());
+    fs::create_dir_all(&target_dir).unwrap();
+    fs::write(target_dir.join("leftover"), b"").unwrap();
+
+    let installed = ensure(&options).unwrap();
+    assert!(installed.downloaded);
+    assert!(Path::new(&installed.path).is_file());
+    assert!(!target_dir.join("leftover").exists());
+    // 暂存目录与移开的旧目录都已清理
+    let leftovers = fs::read_dir(target_dir.parent().unwrap()).unwrap().count();
+    assert_eq!(leftovers, 1);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn requires_checksum_source() {
+    let dir = temp_dir();
+    let source = write_release(&dir);
+    fs::remove_file(dir.join(CHECKSUM_MANIFEST)).unwrap();
+    let mut options = options(&dir, &source);
+
+    let err = ensure(&options).err().unwrap();
+    assert!(err.contains("pass the sha256 option"), "{}", err);
+
+    options.sha256 = Some("xyz".to_string());
+    let err = ensure(&options).err().unwrap();
+    assert!(err.contains("Invalid sha256"), "{}", err);
+
+    // 显式指定 sha256 时不需要校验清单
+    let hash: String = Sha256::digest(fs::read(&source).unwrap())
+      .iter()
+      .map(|b| format!("{:02X}", b))
+      .collect();
+    options.sha256 = Some(hash);
+    assert!(ensure(&options).unwrap().downloaded);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_checksum_mismatch() {
+    let dir = temp_dir();
+    let source = write_release(&dir);
+    let mut options = options(&dir, &source);
+    options.sha256 = Some("0".repeat(64));
+
+    let err = ensure(&options).err().unwrap();
+    assert!(err.contains("Checksum mismatch"), "{}", err);
+    let version_dir = dir.join("cache").join("v0.0.0-test");
+    assert_eq!(fs::read_dir(version_dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
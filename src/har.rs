@@ -0,0 +1,764 @@
+//! HAR 1.2 记录：在传输结束时根据实际发出的请求、响应头与 CurlInfo 计时生成条目
+
+use std::ffi::c_int;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use napi_derive::napi;
+
+use crate::{
+  api::easy_strerror,
+  constants::CurlInfo,
+  info::{info_long, info_off_t, info_string},
+  loader::{CurlFunctions, CurlHandle},
+  response::parse_response_hops,
+  trace::RequestCapture,
+};
+
+// 默认为请求体、响应体各保留的字节数
+const DEFAULT_MAX_BODY_SIZE: u32 = 1024 * 1024;
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarCreator {
+  pub name: String,
+  pub version: String,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarNameValue {
+  pub name: String,
+  pub value: String,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarCookie {
+  pub name: String,
+  pub value: String,
+  pub path: Option<String>,
+  pub domain: Option<String>,
+  /// Set-Cookie 中的原始 Expires
+  pub expires: Option<String>,
+  pub http_only: Option<bool>,
+  pub secure: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarPostData {
+  pub mime_type: String,
+  pub text: String,
+  pub comment: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarRequest {
+  pub method: String,
+  pub url: String,
+  pub http_version: String,
+  pub cookies: Vec<HarCookie>,
+  pub headers: Vec<HarNameValue>,
+  pub query_string: Vec<HarNameValue>,
+  pub post_data: Option<HarPostData>,
+  pub headers_size: i64,
+  pub body_size: i64,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarContent {
+  /// 解码后的大小
+  pub size: i64,
+  /// 压缩节省的字节数
+  pub compression: Option<i64>,
+  pub mime_type: String,
+  pub text: Option<String>,
+  /// 响应体不是 UTF-8 时为 base64
+  pub encoding: Option<String>,
+  pub comment: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarResponse {
+  pub status: i32,
+  pub status_text: String,
+  pub http_version: String,
+  pub cookies: Vec<HarCookie>,
+  pub headers: Vec<HarNameValue>,
+  pub content: HarContent,
+  #[napi(js_name = "redirectURL")]
+  pub redirect_url: String,
+  pub headers_size: i64,
+  pub body_size: i64,
+}
+
+/// 各阶段耗时（毫秒），不适用时为 -1
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarTimings {
+  pub blocked: f64,
+  pub dns: f64,
+  pub connect: f64,
+  pub send: f64,
+  pub wait: f64,
+  pub receive: f64,
+  pub ssl: f64,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarCache {}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarEntry {
+  pub started_date_time: String,
+  /// 总耗时（毫秒），即 TotalTimeT，包含重定向
+  pub time: f64,
+  pub request: HarRequest,
+  pub response: HarResponse,
+  pub cache: HarCache,
+  pub timings: HarTimings,
+  #[napi(js_name = "serverIPAddress")]
+  pub server_ip_address: Option<String>,
+  /// 连接 id（CURLINFO_CONN_ID）
+  pub connection: Option<String>,
+  /// 传输失败时的错误信息
+  pub comment: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct HarLog {
+  pub version: String,
+  pub creator: HarCreator,
+  pub entries: Vec<HarEntry>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct Har {
+  pub log: HarLog,
+}
+
+#[napi(object)]
+pub struct HarRecorderOptions {
+  /// 请求体、响应体各保留的最大字节数，超出部分截断，默认 1MB；为 0 时不记录正文
+  pub max_body_size: Option<u32>,
+  pub creator: Option<HarCreator>,
+}
+
+// 挂载到 Curl 或 CurlMulti 上，在传输线程中追加条目
+pub(crate) struct HarStore {
+  max_body_size: usize,
+  creator: HarCreator,
+  entries: Mutex<Vec<HarEntry>>,
+}
+
+/// 结束时交给 HarStore 的传输
+pub(crate) struct HarTransfer<'a> {
+  pub lib: &'static CurlFunctions,
+  pub handle: CurlHandle,
+  pub code: c_int,
+  pub request: Option<RequestCapture>,
+  pub headers: &'a [u8],
+  /// 流式读取响应体时为空
+  pub body: Option<&'a [u8]>,
+}
+
+impl HarStore {
+  pub(crate) fn max_body_size(&self) -> usize {
+    self.max_body_size
+  }
+
+  pub(crate) fn record(&self, transfer: HarTransfer) {
+    let entry = unsafe { build_entry(transfer, self.max_body_size) };
+    if let Ok(mut entries) = self.entries.lock() {
+      entries.push(entry);
+    }
+  }
+}
+
+/// HAR 记录器，通过 Curl.setHarRecorder 或 CurlMulti.setHarRecorder 挂载
+///
+/// JSON.stringify(recorder) 即得到 HAR 文件内容
+#[napi]
+pub struct HarRecorder {
+  store: Arc<HarStore>,
+}
+
+#[napi]
+impl HarRecorder {
+  #[napi(constructor)]
+  pub fn new(options: Option<HarRecorderOptions>) -> Self {
+    let (max_body_size, creator) = match options {
+      Some(options) => (options.max_body_size, options.creator),
+      None => (None, None),
+    };
+    HarRecorder {
+      store: Arc::new(HarStore {
+        max_body_size: max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE) as usize,
+        creator: creator.unwrap_or_else(|| HarCreator {
+          name: "@tocha688/libcurl".to_string(),
+          version: env!("CARGO_PKG_VERSION").to_string(),
+        }),
+        entries: Mutex::new(Vec::new()),
+      }),
+    }
+  }
+
+  /// 已记录的条目数
+  #[napi(getter)]
+  pub fn length(&self) -> u32 {
+    self.store.entries.lock().map_or(0, |entries| entries.len() as u32)
+  }
+
+  /// 清空已记录的条目
+  #[napi]
+  pub fn clear(&self) {
+    if let Ok(mut entries) = self.store.entries.lock() {
+      entries.clear();
+    }
+  }
+
+  /// 生成 HAR 1.2 对象
+  #[napi]
+  pub fn get_har(&self) -> Har {
+    Har {
+      log: HarLog {
+        version: "1.2".to_string(),
+        creator: self.store.creator.clone(),
+        entries: self
+          .store
+          .entries
+          .lock()
+          .map(|entries| entries.clone())
+          .unwrap_or_default(),
+      },
+    }
+  }
+
+  #[napi(js_name = "toJSON")]
+  pub fn to_json(&self) -> Har {
+    self.get_har()
+  }
+
+  pub(crate) fn store(&self) -> Arc<HarStore> {
+    self.store.clone()
+  }
+}
+
+unsafe fn build_entry(transfer: HarTransfer, max_body_size: usize) -> HarEntry {
+  let HarTransfer {
+    lib,
+    handle,
+    code,
+    request,
+    headers,
+    body,
+  } = transfer;
+  let timings = read_timings(lib, handle);
+  let total = info_off_t(lib, handle, CurlInfo::TotalTimeT).unwrap_or_default() as f64 / 1000.0;
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+    .unwrap_or_default();
+
+  HarEntry {
+    started_date_time: iso8601(now - total),
+    time: total,
+    request: build_request(lib, handle, request.unwrap_or_default()),
+    response: build_response(lib, handle, headers, body, max_body_size),
+    cache: HarCache {},
+    timings,
+    server_ip_address: info_string(lib, handle, CurlInfo::PrimaryIp).filter(|ip| !ip.is_empty()),
+    connection: info_off_t(lib, handle, CurlInfo::ConnId)
+      .filter(|id| *id >= 0)
+      .map(|id| id.to_string()),
+    comment: (code != 0).then(|| format!("CurlError {}: {}", code, easy_strerror(lib, code))),
+  }
+}
+
+unsafe fn build_request(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  request: RequestCapture,
+) -> HarRequest {
+  let url = info_string(lib, handle, CurlInfo::EffectiveUrl).unwrap_or_default();
+  let text = String::from_utf8_lossy(&request.headers);
+  let mut lines = text
+    .split('\n')
+    .map(|line| line.strip_suffix('\r').unwrap_or(line))
+    .filter(|line| !line.is_empty());
+  // 请求行，如 "GET /path HTTP/1.1"，HTTP/2 及以上同样按此格式输出
+  let request_line: Vec<&str> = lines.next().unwrap_or_default().split(' ').collect();
+  let headers: Vec<HarNameValue> = lines
+    .filter_map(|line| line.split_once(':'))
+    .map(|(name, value)| HarNameValue {
+      name: name.trim().to_string(),
+      value: value.trim().to_string(),
+    })
+    .collect();
+
+  let method = match request_line.first() {
+    Some(method) if !method.is_empty() => method.to_string(),
+    _ => info_string(lib, handle, CurlInfo::EffectiveMethod).unwrap_or_else(|| "GET".to_string()),
+  };
+  let http_version = request_line
+    .last()
+    .filter(|version| version.starts_with("HTTP/"))
+    .map(|version| version.to_string())
+    .unwrap_or_default();
+  let cookies = headers
+    .iter()
+    .filter(|header| header.name.eq_ignore_ascii_case("cookie"))
+    .flat_map(|header| header.value.split(';'))
+    .filter_map(parse_pair)
+    .map(|(name, value)| HarCookie {
+      name,
+      value,
+      path: None,
+      domain: None,
+      expires: None,
+      http_only: None,
+      secure: None,
+    })
+    .collect();
+  let post_data = (request.body_size > 0).then(|| HarPostData {
+    mime_type: find_header(&headers, "content-type").unwrap_or_default(),
+    text: String::from_utf8_lossy(&request.body).into_owned(),
+    comment: (request.body_size > request.body.len()).then(|| "truncated".to_string()),
+  });
+
+  HarRequest {
+    method,
+    query_string: parse_query(&url),
+    url,
+    http_version,
+    cookies,
+    headers_size: if request.headers.is_empty() {
+      -1
+    } else {
+      request.headers.len() as i64
+    },
+    headers,
+    post_data,
+    body_size: request.body_size as i64,
+  }
+}
+
+/// 保留 body 的前 max 字节，是 UTF-8 文本时一并返回；截断点落在多字节字符中间时退回到字符边界
+fn truncate_body(body: &[u8], max: usize) -> (&[u8], Option<&str>) {
+  let kept = &body[..body.len().min(max)];
+  match std::str::from_utf8(kept) {
+    Ok(text) => (kept, Some(text)),
+    // error_len 为 None 表示结尾的字符不完整，只可能由截断造成
+    Err(err) if kept.len() < body.len() && err.error_len().is_none() => {
+      let kept = &kept[..err.valid_up_to()];
+      (kept, std::str::from_utf8(kept).ok())
+    }
+    Err(_) => (kept, None),
+  }
+}
+
+unsafe fn build_response(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  headers: &[u8],
+  body: Option<&[u8]>,
+  max_body_size: usize,
+) -> HarResponse {
+  let hop = parse_response_hops(headers).pop();
+  let (status_text, http_version, headers) = match hop {
+    Some(hop) => (
+      hop.reason,
+      format!("HTTP/{}", hop.http_version),
+      hop
+        .headers
+        .into_iter()
+        .map(|header| HarNameValue {
+          name: header.name,
+          value: header.value,
+        })
+        .collect(),
+    ),
+    None => (String::new(), String::new(), Vec::new()),
+  };
+  let cookies = headers
+    .iter()
+    .filter(|header| header.name.eq_ignore_ascii_case("set-cookie"))
+    .filter_map(|header| parse_set_cookie(&header.value))
+    .collect();
+  let body_size = info_off_t(lib, handle, CurlInfo::SizeDownloadT).unwrap_or_default();
+
+  let mut content = HarContent {
+    size: body_size,
+    compression: None,
+    mime_type: info_string(lib, handle, CurlInfo::ContentType).unwrap_or_default(),
+    text: None,
+    encoding: None,
+    comment: None,
+  };
+  match body {
+    Some(body) => {
+      content.size = body.len() as i64;
+      if find_header(&headers, "content-encoding").is_some() {
+        content.compression = Some(body.len() as i64 - body_size);
+      }
+      let (kept, text) = truncate_body(body, max_body_size);
+      if !kept.is_empty() {
+        match text {
+          Some(text) => content.text = Some(text.to_string()),
+          None => {
+            content.text = Some(base64::engine::general_purpose::STANDARD.encode(kept));
+            content.encoding = Some("base64".to_string());
+          }
+        }
+      }
+      if kept.len() < body.len() {
+        content.comment = Some("truncated".to_string());
+      }
+    }
+    None => content.comment = Some("streamed".to_string()),
+  }
+
+  HarResponse {
+    status: info_long(lib, handle, CurlInfo::ResponseCode).unwrap_or_default() as i32,
+    status_text,
+    http_version,
+    cookies,
+    headers,
+    content,
+    redirect_url: info_string(lib, handle, CurlInfo::RedirectUrl).unwrap_or_default(),
+    headers_size: info_long(lib, handle, CurlInfo::HeaderSize).unwrap_or(-1),
+    body_size,
+  }
+}
+
+// 由 *_T 时间点（微秒）换算各阶段耗时，连接复用时 dns、connect 为 -1
+unsafe fn read_timings(lib: &'static CurlFunctions, handle: CurlHandle) -> HarTimings {
+  let time = |info| info_off_t(lib, handle, info).unwrap_or_default();
+  har_timings(&TransferTimes {
+    name_lookup: time(CurlInfo::NameLookupTimeT),
+    connect: time(CurlInfo::ConnectTimeT),
+    app_connect: time(CurlInfo::AppConnectTimeT),
+    pre_transfer: time(CurlInfo::PreTransferTimeT),
+    post_transfer: time(CurlInfo::PostTransferTimeT),
+    start_transfer: time(CurlInfo::StartTransferTimeT),
+    total: time(CurlInfo::TotalTimeT),
+    redirect: time(CurlInfo::RedirectTimeT),
+  })
+}
+
+// libcurl 报告的各时间点（微秒）
+struct TransferTimes {
+  name_lookup: i64,
+  connect: i64,
+  app_connect: i64,
+  pre_transfer: i64,
+  post_transfer: i64,
+  start_transfer: i64,
+  total: i64,
+  redirect: i64,
+}
+
+// 重定向之前各跳的耗时计入 blocked，其余时间点从最后一次请求开始计算；
+// 时间点按阶段顺序单调递增，保证各阶段之和等于总耗时
+fn har_timings(times: &TransferTimes) -> HarTimings {
+  let redirect = times.redirect.max(0);
+  let after = |us: i64, previous: i64| (us - redirect).max(previous);
+  let reused = times.connect == 0;
+  let name_lookup = after(times.name_lookup, 0);
+  let connect = after(times.connect.max(times.app_connect), name_lookup);
+  let pre_transfer = after(times.pre_transfer, connect);
+  // 请求体发送完成的时间点，旧版 libcurl 不支持时视为 pre_transfer
+  let post_transfer = match times.post_transfer {
+    0 => pre_transfer,
+    post_transfer => after(post_transfer, pre_transfer),
+  };
+  let start_transfer = after(times.start_transfer, post_transfer);
+  let total = after(times.total, start_transfer);
+  let ms = |us: i64| us as f64 / 1000.0;
+  // 复用连接时没有 dns 与 connect，发送前的耗时计入 blocked
+  let blocked = if reused {
+    redirect + pre_transfer
+  } else {
+    redirect
+  };
+
+  HarTimings {
+    blocked: if blocked > 0 { ms(blocked) } else { -1.0 },
+    dns: if reused { -1.0 } else { ms(name_lookup) },
+    connect: if reused {
+      -1.0
+    } else {
+      ms(connect - name_lookup)
+    },
+    send: ms(post_transfer - if reused { pre_transfer } else { connect }),
+    wait: ms(start_transfer - post_transfer),
+    receive: ms(total - start_transfer),
+    ssl: if reused || times.app_connect == 0 {
+      -1.0
+    } else {
+      ms((times.app_connect - times.connect).max(0))
+    },
+  }
+}
+
+fn find_header(headers: &[HarNameValue], name: &str) -> Option<String> {
+  headers
+    .iter()
+    .find(|header| header.name.eq_ignore_ascii_case(name))
+    .map(|header| header.value.clone())
+}
+
+fn parse_pair(pair: &str) -> Option<(String, String)> {
+  let pair = pair.trim();
+  if pair.is_empty() {
+    return None;
+  }
+  Some(match pair.split_once('=') {
+    Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+    None => (pair.to_string(), String::new()),
+  })
+}
+
+fn parse_query(url: &str) -> Vec<HarNameValue> {
+  let url = url.split('#').next().unwrap_or_default();
+  let Some((_, query)) = url.split_once('?') else {
+    return Vec::new();
+  };
+  query
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .map(|pair| {
+      let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+      HarNameValue {
+        name: name.to_string(),
+        value: value.to_string(),
+      }
+    })
+    .collect()
+}
+
+fn parse_set_cookie(value: &str) -> Option<HarCookie> {
+  let mut parts = value.split(';');
+  let (name, value) = parse_pair(parts.next()?)?;
+  let mut cookie = HarCookie {
+    name,
+    value,
+    path: None,
+    domain: None,
+    expires: None,
+    http_only: None,
+    secure: None,
+  };
+  for (key, value) in parts.filter_map(parse_pair) {
+    match key.to_ascii_lowercase().as_str() {
+      "path" => cookie.path = Some(value),
+      "domain" => cookie.domain = Some(value),
+      "expires" => cookie.expires = Some(value),
+      "httponly" => cookie.http_only = Some(true),
+      "secure" => cookie.secure = Some(true),
+      _ => {}
+    }
+  }
+  Some(cookie)
+}
+
+// 毫秒时间戳格式化为 UTC 的 ISO 8601，如 2024-01-02T03:04:05.678Z
+fn iso8601(ms: f64) -> String {
+  let ms = ms.max(0.0) as i64;
+  let (days, rest) = (ms.div_euclid(86_400_000), ms.rem_euclid(86_400_000));
+  // 公历换算，见 Howard Hinnant 的 civil_from_days
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+    year,
+    month,
+    day,
+    rest / 3_600_000,
+    rest / 60_000 % 60,
+    rest / 1000 % 60,
+    rest % 1000
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn timings_sum(timings: &HarTimings) -> f64 {
+    [
+      timings.blocked,
+      timings.dns,
+      timings.connect,
+      timings.send,
+      timings.wait,
+      timings.receive,
+    ]
+    .iter()
+    .filter(|value| **value >= 0.0)
+    .sum()
+  }
+
+  #[test]
+  fn splits_phases_of_new_connection() {
+    let timings = har_timings(&TransferTimes {
+      name_lookup: 1_000,
+      connect: 3_000,
+      app_connect: 7_000,
+      pre_transfer: 7_500,
+      post_transfer: 8_000,
+      start_transfer: 20_000,
+      total: 25_000,
+      redirect: 0,
+    });
+    assert_eq!(timings.blocked, -1.0);
+    assert_eq!(timings.dns, 1.0);
+    assert_eq!(timings.connect, 6.0);
+    assert_eq!(timings.ssl, 4.0);
+    assert_eq!(timings.send, 1.0);
+    assert_eq!(timings.wait, 12.0);
+    assert_eq!(timings.receive, 5.0);
+    assert_eq!(timings_sum(&timings), 25.0);
+  }
+
+  #[test]
+  fn redirect_time_is_blocked() {
+    let timings = har_timings(&TransferTimes {
+      name_lookup: 11_000,
+      connect: 12_000,
+      app_connect: 0,
+      pre_transfer: 12_500,
+      post_transfer: 0,
+      start_transfer: 30_000,
+      total: 40_000,
+      redirect: 10_000,
+    });
+    assert_eq!(timings.blocked, 10.0);
+    assert_eq!(timings.dns, 1.0);
+    assert_eq!(timings.ssl, -1.0);
+    assert_eq!(timings.receive, 10.0);
+    assert_eq!(timings_sum(&timings), 40.0);
+  }
+
+  #[test]
+  fn reused_connection_has_no_connect_phases() {
+    let timings = har_timings(&TransferTimes {
+      name_lookup: 200,
+      connect: 0,
+      app_connect: 0,
+      pre_transfer: 500,
+      post_transfer: 600,
+      start_transfer: 5_000,
+      total: 6_000,
+      redirect: 2_000,
+    });
+    assert_eq!(timings.dns, -1.0);
+    assert_eq!(timings.connect, -1.0);
+    assert_eq!(timings_sum(&timings), 6.0);
+  }
+
+  #[test]
+  fn failed_transfer_keeps_phases_non_negative() {
+    let timings = har_timings(&TransferTimes {
+      name_lookup: 2_000,
+      connect: 0,
+      app_connect: 0,
+      pre_transfer: 0,
+      post_transfer: 0,
+      start_transfer: 0,
+      total: 3_000,
+      redirect: 0,
+    });
+    assert!([timings.send, timings.wait, timings.receive]
+      .iter()
+      .all(|value| *value >= 0.0));
+    assert_eq!(timings_sum(&timings), 3.0);
+  }
+
+  #[test]
+  fn formats_iso8601() {
+    assert_eq!(iso8601(0.0), "1970-01-01T00:00:00.000Z");
+    assert_eq!(iso8601(1_704_164_645_678.0), "2024-01-02T03:04:05.678Z");
+    // 闰日与世纪闰年
+    assert_eq!(iso8601(951_782_400_000.0), "2000-02-29T00:00:00.000Z");
+    assert_eq!(iso8601(4_107_542_399_999.0), "2100-02-28T23:59:59.999Z");
+    assert_eq!(iso8601(-5.0), "1970-01-01T00:00:00.000Z");
+  }
+
+  #[test]
+  fn parses_set_cookie_attributes() {
+    let cookie = parse_set_cookie(
+      "sid=abc=1; Path=/; Domain=.example.com; Expires=Wed, 21 Oct 2026 07:28:00 GMT; HttpOnly; secure",
+    )
+    .unwrap();
+    assert_eq!(cookie.name, "sid");
+    assert_eq!(cookie.value, "abc=1");
+    assert_eq!(cookie.path.as_deref(), Some("/"));
+    assert_eq!(cookie.domain.as_deref(), Some(".example.com"));
+    assert_eq!(
+      cookie.expires.as_deref(),
+      Some("Wed, 21 Oct 2026 07:28:00 GMT")
+    );
+    assert_eq!(cookie.http_only, Some(true));
+    assert_eq!(cookie.secure, Some(true));
+  }
+
+  #[test]
+  fn parses_bare_set_cookie() {
+    let cookie = parse_set_cookie(" flag ; SameSite=Lax").unwrap();
+    assert_eq!(cookie.name, "flag");
+    assert_eq!(cookie.value, "");
+    assert!(cookie.path.is_none() && cookie.http_only.is_none() && cookie.secure.is_none());
+    assert!(parse_set_cookie("").is_none());
+    assert!(parse_set_cookie(" ; Path=/").is_none());
+  }
+
+  #[test]
+  fn parses_query_string() {
+    let pairs: Vec<(String, String)> =
+      parse_query("https://example.com/a?x=1&flag&&y=a=b#frag?z=2")
+        .into_iter()
+        .map(|pair| (pair.name, pair.value))
+        .collect();
+    assert_eq!(
+      pairs,
+      [
+        ("x".to_string(), "1".to_string()),
+        ("flag".to_string(), String::new()),
+        ("y".to_string(), "a=b".to_string()),
+      ]
+    );
+    assert!(parse_query("https://example.com/a").is_empty());
+    assert!(parse_query("https://example.com/#a?b=1").is_empty());
+  }
+
+  #[test]
+  fn truncates_body_at_char_boundary() {
+    let body = "ab中文".as_bytes();
+    // 截断在“中”的第二个字节之后
+    assert_eq!(truncate_body(body, 4), (&body[..2], Some("ab")));
+    assert_eq!(truncate_body(body, 5), (&body[..5], Some("ab中")));
+    assert_eq!(truncate_body(body, 100), (body, Some("ab中文")));
+    // 截断范围内本就不是 UTF-8 时按原长度保留，记为 base64
+    let binary = [0xff, 0xfe, 0x00, 0x01];
+    assert_eq!(truncate_body(&binary, 3), (&binary[..3], None));
+    // 未截断时不完整的结尾不是截断造成的
+    assert_eq!(truncate_body(&body[..4], 100), (&body[..4], None));
+  }
+}
@@ -0,0 +1,101 @@
+//! 按 C 类型读取 curl_easy_getinfo，可在传输所在的线程中调用
+
+use std::ffi::{c_char, c_double, c_int, c_long, c_void, CStr};
+
+use napi::bindgen_prelude::{BigInt, Either5};
+
+use crate::{
+  constants::{CurlInfo, CURLINFO_DOUBLE, CURLINFO_LONG, CURLINFO_OFF_T},
+  loader::{CurlCertInfo, CurlFunctions, CurlHandle, CurlSlistNode},
+};
+
+/// Curl.getInfoValue 的返回值：字符串、数字、BigInt、字符串数组、证书链
+pub type CurlInfoValue = Either5<String, f64, BigInt, Vec<String>, Vec<Vec<String>>>;
+
+/// CURLINFO_LONG 类型的信息
+pub(crate) unsafe fn info_long(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  info: CurlInfo,
+) -> Option<i64> {
+  let mut value: c_long = 0;
+  let code = (lib.easy_getinfo)(handle, info as c_int, &mut value as *mut _ as *mut c_void);
+  (code == 0).then_some(value as i64)
+}
+
+/// CURLINFO_OFF_T 类型的信息，libcurl 不支持该信息时为空
+pub(crate) unsafe fn info_off_t(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  info: CurlInfo,
+) -> Option<i64> {
+  let mut value: i64 = 0;
+  let code = (lib.easy_getinfo)(handle, info as c_int, &mut value as *mut _ as *mut c_void);
+  (code == 0).then_some(value)
+}
+
+/// CURLINFO_DOUBLE 类型的信息
+pub(crate) unsafe fn info_double(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  info: CurlInfo,
+) -> Option<f64> {
+  let mut value: c_double = 0.0;
+  let code = (lib.easy_getinfo)(handle, info as c_int, &mut value as *mut _ as *mut c_void);
+  (code == 0).then_some(value)
+}
+
+/// 按类型位读取数值类型（LONG、DOUBLE、OFF_T）的信息，其他类型为空
+pub(crate) unsafe fn info_number(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  info: CurlInfo,
+) -> Option<f64> {
+  match info.type_bits() {
+    CURLINFO_LONG => info_long(lib, handle, info).map(|value| value as f64),
+    CURLINFO_DOUBLE => info_double(lib, handle, info),
+    CURLINFO_OFF_T => info_off_t(lib, handle, info).map(|value| value as f64),
+    _ => None,
+  }
+}
+
+/// CURLINFO_STRING 类型的信息，未设置时为空
+pub(crate) unsafe fn info_string(
+  lib: &'static CurlFunctions,
+  handle: CurlHandle,
+  info: CurlInfo,
+) -> Option<String> {
+  let mut value: *mut c_char = std::ptr::null_mut();
+  let code = (lib.easy_getinfo)(handle, info as c_int, &mut value as *mut _ as *mut c_void);
+  if code != 0 || value.is_null() {
+    return None;
+  }
+  Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+}
+
+/// 读取 curl_slist 链表中的字符串，不释放链表
+pub(crate) unsafe fn slist_strings(mut current: *const CurlSlistNode) -> Vec<String> {
+  let mut strings = Vec::new();
+  while !current.is_null() {
+    let node = &*current;
+    if !node.data.is_null() {
+      strings.push(CStr::from_ptr(node.data).to_string_lossy().into_owned());
+    }
+    current = node.next;
+  }
+  strings
+}
+
+/// 读取 CURLINFO_CERTINFO 返回的证书链，每个证书为 "名称:值" 列表
+pub(crate) unsafe fn certinfo_strings(certinfo: *const CurlCertInfo) -> Vec<Vec<String>> {
+  if certinfo.is_null() {
+    return Vec::new();
+  }
+  let certinfo = &*certinfo;
+  if certinfo.certinfo.is_null() {
+    return Vec::new();
+  }
+  (0..certinfo.num_of_certs.max(0) as usize)
+    .map(|index| slist_strings(*certinfo.certinfo.add(index)))
+    .collect()
+}
@@ -10,3 +10,18 @@ pub mod curl_multi;
 pub mod socket;
 pub mod logger;
 pub mod callbacks;
+pub mod stream;
+pub mod response;
+pub mod websocket;
+pub mod mime;
+pub mod share;
+pub mod url;
+pub mod uv;
+pub mod push;
+pub mod options;
+pub mod library;
+pub mod download;
+pub mod trace;
+pub mod info;
+pub mod har;
+pub mod stats;
@@ -3,8 +3,21 @@ use std::sync::RwLock;
 use napi_derive::napi;
 use once_cell::sync::Lazy;
 
-const CURL_IMPERSONATE_VERSION: &str = "v1.0.0";
-const BASE_URL: &str = "https://github.com/lexiforest/@tocha688/libcurl/releases/download";
+use crate::loader::is_library_loaded;
+use crate::log_warn;
+
+// 默认使用的发布版本：发布本包时验证过的 curl-impersonate 版本，随本包升级，不会自动跟随上游最新发布
+// 优先级依次为 ensureLibrary 的 version 选项、环境变量 CURL_IMPERSONATE_VERSION、此常量
+pub const CURL_IMPERSONATE_VERSION: &str = "v1.0.0";
+pub const BASE_URL: &str = "https://github.com/lexiforest/curl-impersonate/releases/download";
+
+/// 未显式指定时使用的发布版本
+pub fn default_version() -> String {
+    env::var("CURL_IMPERSONATE_VERSION")
+        .ok()
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| CURL_IMPERSONATE_VERSION.to_string())
+}
 
 #[derive(Debug, Clone)]
 pub struct PlatformInfo {
@@ -49,9 +62,8 @@ impl PlatformInfo {
         PlatformInfo { arch, os, variant }
     }
 
-    pub fn get_download_filename(&self, is_libcurl: bool) -> String {
-        let prefix = if is_libcurl { "lib@tocha688/libcurl" } else { "@tocha688/libcurl" };
-        
+    /// 发布包所在的平台目录名，如 x86_64-linux-gnu
+    pub fn target(&self) -> String {
         // 特殊处理 Windows 架构映射
         let arch = if self.os.contains("win32") {
             match self.arch.as_str() {
@@ -61,24 +73,47 @@ impl PlatformInfo {
         } else {
             &self.arch
         };
+        format!("{}-{}", arch, self.os)
+    }
 
-        format!("{}-{}-{}-{}.tar.gz", prefix, CURL_IMPERSONATE_VERSION, arch, self.os)
+    pub fn get_download_filename(&self, version: &str, is_libcurl: bool) -> String {
+        let prefix = if is_libcurl { "libcurl-impersonate" } else { "curl-impersonate" };
+        format!("{}-{}.{}.tar.gz", prefix, version, self.target())
     }
 
-    pub fn get_download_url(&self, is_libcurl: bool) -> String {
-        let filename = self.get_download_filename(is_libcurl);
-        format!("{}/{}/{}", BASE_URL, CURL_IMPERSONATE_VERSION, filename)
+    /// base 为发布包下载地址前缀，默认为 GitHub releases
+    pub fn get_download_url(&self, base: Option<&str>, version: &str, is_libcurl: bool) -> String {
+        let filename = self.get_download_filename(version, is_libcurl);
+        let base = base.unwrap_or(BASE_URL).trim_end_matches('/');
+        format!("{}/{}/{}", base, version, filename)
     }
-    
 }
 
 // 使用 RwLock 替代 mutable static，更安全
 static LIB_PATH: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
 
+/// 设置全局默认库的路径；全局库加载后不能再更换，其他构建请使用 CurlLibrary 加载
 #[napi]
 pub fn set_lib_path(path: String) {
-    if let Ok(mut lib_path) = LIB_PATH.write() {
-        *lib_path = Some(path);
+    apply_lib_path(path);
+}
+
+/// 同 setLibPath，返回路径是否生效
+pub(crate) fn apply_lib_path(path: String) -> bool {
+    if is_library_loaded() && get_lib_path().as_deref() != Some(path.as_str()) {
+        log_warn!(
+            "LibPath",
+            "Global libcurl is already loaded, ignoring setLibPath({}); use CurlLibrary instead",
+            path
+        );
+        return false;
+    }
+    match LIB_PATH.write() {
+        Ok(mut lib_path) => {
+            *lib_path = Some(path);
+            true
+        }
+        Err(_) => false,
     }
 }
 
@@ -0,0 +1,100 @@
+//! 指定路径加载的 libcurl 构建，可与全局默认库在同一进程中并存
+
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use crate::{
+  api::{capabilities, set_global_trace, version, version_info, CurlCapability, VersionInfo},
+  libpath::get_lib_path,
+  loader::{napi_load_library, napi_load_library_at, same_library, CurlFunctions},
+  options::{list_options, CurlOptionInfo},
+};
+
+/// 一个已加载的 libcurl 构建，传给 Curl、CurlMulti、CurlShare、CurlUrl 的构造函数后由其使用
+///
+/// 同一路径只加载一次；来自不同库的句柄不能混用
+#[napi]
+pub struct CurlLibrary {
+  lib: &'static CurlFunctions,
+  path: String,
+}
+
+#[napi]
+impl CurlLibrary {
+  /// 加载指定路径的 libcurl
+  #[napi(constructor)]
+  pub fn new(path: String) -> Result<Self> {
+    let lib = napi_load_library_at(&path)?;
+    Ok(CurlLibrary { lib, path })
+  }
+
+  /// 全局默认库，即 setLibPath 指定的路径
+  #[napi(factory)]
+  pub fn global() -> Result<Self> {
+    let lib = napi_load_library()?;
+    Ok(CurlLibrary {
+      lib,
+      path: get_lib_path().unwrap_or_default(),
+    })
+  }
+
+  #[napi(getter)]
+  pub fn path(&self) -> String {
+    self.path.clone()
+  }
+
+  /// 是否与全局默认库是同一个库
+  #[napi]
+  pub fn is_global(&self) -> bool {
+    napi_load_library().is_ok_and(|lib| same_library(lib, self.lib))
+  }
+
+  /// curl_version() 字符串
+  #[napi]
+  pub fn get_version(&self) -> Result<String> {
+    version(self.lib)
+  }
+
+  #[napi]
+  pub fn get_version_info(&self) -> Result<VersionInfo> {
+    version_info(self.lib)
+  }
+
+  #[napi]
+  pub fn get_capabilities(&self) -> Vec<CurlCapability> {
+    capabilities(self.lib)
+  }
+
+  /// 该库的 curl_global_trace
+  #[napi]
+  pub fn global_trace(&self, config: String) -> Result<()> {
+    set_global_trace(self.lib, &config)
+  }
+
+  #[napi]
+  pub fn get_curl_options(&self) -> Result<Vec<CurlOptionInfo>> {
+    list_options(self.lib)
+  }
+}
+
+/// 未指定库时使用全局默认库
+pub(crate) fn resolve_library(library: Option<&CurlLibrary>) -> Result<&'static CurlFunctions> {
+  match library {
+    Some(library) => Ok(library.lib),
+    None => napi_load_library(),
+  }
+}
+
+/// 检查两个句柄来自同一个库
+pub(crate) fn check_same_library(
+  a: &'static CurlFunctions,
+  b: &'static CurlFunctions,
+) -> Result<()> {
+  if same_library(a, b) {
+    Ok(())
+  } else {
+    Err(Error::from_reason(
+      "Handles loaded from different libcurl libraries cannot be mixed",
+    ))
+  }
+}
@@ -1,8 +1,10 @@
 use libloading::{Library, Symbol};
 use napi::{Error, Status};
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use std::{
-  ffi::c_short, os::raw::{c_char, c_int, c_long, c_uint, c_void}
+  collections::HashMap,
+  ffi::c_short, os::raw::{c_char, c_int, c_long, c_uint, c_void},
+  sync::Mutex,
 };
 
 use crate::libpath::get_lib_path;
@@ -18,8 +20,11 @@ pub type CurlUrl = *mut std::ffi::c_void;
 pub type CurlForm = *mut std::ffi::c_void;
 pub type CurlHttpPost = *mut c_void;
 
-// 存储加载的函数实例
-static CURL_FUNCTIONS: OnceCell<CurlFunctions> = OnceCell::new();
+// 全局默认库（setLibPath 指定的路径）
+static CURL_FUNCTIONS: OnceCell<&'static CurlFunctions> = OnceCell::new();
+// 按路径缓存已加载的库，同一路径只加载一次，其函数实例可用于判断句柄是否来自同一个库
+static LIBRARIES: Lazy<Mutex<HashMap<String, &'static CurlFunctions>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
 
 // 回调函数类型
 pub type WriteCallback = unsafe extern "C" fn(
@@ -77,6 +82,92 @@ pub struct CurlSlistNode {
   pub next: *mut CurlSlistNode,
 }
 
+// CURLINFO_CERTINFO 返回的证书链，由 libcurl 持有
+#[repr(C)]
+pub struct CurlCertInfo {
+  pub num_of_certs: c_int,
+  pub certinfo: *mut *mut CurlSlistNode,
+}
+
+// curl_easy_option_by_id 等返回的选项描述
+#[repr(C)]
+pub struct CurlEasyOption {
+  pub name: *const c_char,
+  pub id: c_int,
+  pub type_: c_int,
+  pub flags: c_uint,
+}
+
+// CURLOPTTYPE_BLOB 选项的参数
+#[repr(C)]
+pub struct CurlBlob {
+  pub data: *mut c_void,
+  pub len: usize,
+  pub flags: c_uint,
+}
+
+// WebSocket 帧元信息
+#[repr(C)]
+pub struct CurlWsFrame {
+  pub age: c_int,
+  pub flags: c_int,
+  pub offset: i64,
+  pub bytesleft: i64,
+  pub len: usize,
+}
+
+// curl_easy_header 返回的头部结构体
+#[repr(C)]
+pub struct CurlHeader {
+  pub name: *mut c_char,
+  pub value: *mut c_char,
+  pub amount: usize,
+  pub index: usize,
+  pub origin: c_uint,
+  pub anchor: *mut c_void,
+}
+
+// curl_version_info 返回的结构体，age 之后的字段按 age 逐步追加
+#[repr(C)]
+pub struct CurlVersionInfoData {
+  pub age: c_int,
+  pub version: *const c_char,
+  pub version_num: c_uint,
+  pub host: *const c_char,
+  pub features: c_int,
+  pub ssl_version: *const c_char,
+  pub ssl_version_num: c_long,
+  pub libz_version: *const c_char,
+  pub protocols: *const *const c_char,
+  // age >= 1
+  pub ares: *const c_char,
+  pub ares_num: c_int,
+  // age >= 2
+  pub libidn: *const c_char,
+  // age >= 3
+  pub iconv_ver_num: c_int,
+  pub libssh_version: *const c_char,
+  // age >= 4
+  pub brotli_ver_num: c_uint,
+  pub brotli_version: *const c_char,
+  // age >= 5
+  pub nghttp2_ver_num: c_uint,
+  pub nghttp2_version: *const c_char,
+  pub quic_version: *const c_char,
+  // age >= 6
+  pub cainfo: *const c_char,
+  pub capath: *const c_char,
+  // age >= 7
+  pub zstd_ver_num: c_uint,
+  pub zstd_version: *const c_char,
+  // age >= 8
+  pub hyper_version: *const c_char,
+  // age >= 9
+  pub gsasl_version: *const c_char,
+  // age >= 10
+  pub feature_names: *const *const c_char,
+}
+
 // Easy interface 函数类型 - 完整版本
 pub type CurlEasyInit = unsafe extern "C" fn() -> CurlHandle;
 pub type CurlEasyCleanup = unsafe extern "C" fn(handle: CurlHandle);
@@ -101,18 +192,17 @@ pub type CurlEasyUnescape = unsafe extern "C" fn(
 pub type CurlEasyHeader = unsafe extern "C" fn(
   handle: CurlHandle,
   name: *const c_char,
-  size: usize,
-  amount: usize,
   index: usize,
   origin: c_uint,
-  request: *mut c_void,
-) -> *mut c_void;
+  request: c_int,
+  hout: *mut *mut CurlHeader,
+) -> c_int;
 pub type CurlEasyNextheader = unsafe extern "C" fn(
   handle: CurlHandle,
   origin: c_uint,
   request: c_int,
-  prev: *mut c_void,
-) -> *mut c_void;
+  prev: *mut CurlHeader,
+) -> *mut CurlHeader;
 pub type CurlEasyOptionById = unsafe extern "C" fn(id: c_int) -> *const c_void;
 pub type CurlEasyOptionByName = unsafe extern "C" fn(name: *const c_char) -> *const c_void;
 pub type CurlEasyOptionNext = unsafe extern "C" fn(prev: *const c_void) -> *const c_void;
@@ -209,6 +299,7 @@ pub type CurlMimeData =
   unsafe extern "C" fn(part: CurlMimepart, data: *const c_char, datasize: usize) -> c_int;
 pub type CurlMimeDataCb = unsafe extern "C" fn(
   part: CurlMimepart,
+  datasize: i64,
   readfunc: ReadCallback,
   seekfunc: *mut c_void,
   freefunc: *mut c_void,
@@ -220,7 +311,8 @@ pub type CurlMimeFiledata =
   unsafe extern "C" fn(part: CurlMimepart, filename: *const c_char) -> c_int;
 pub type CurlMimeFilename =
   unsafe extern "C" fn(part: CurlMimepart, filename: *const c_char) -> c_int;
-pub type CurlMimeHeaders = unsafe extern "C" fn(part: CurlMimepart, headers: CurlSlist) -> c_int;
+pub type CurlMimeHeaders =
+  unsafe extern "C" fn(part: CurlMimepart, headers: CurlSlist, take_ownership: c_int) -> c_int;
 pub type CurlMimeSubparts = unsafe extern "C" fn(part: CurlMimepart, subparts: CurlMime) -> c_int;
 pub type CurlMimeType = unsafe extern "C" fn(part: CurlMimepart, mimetype: *const c_char) -> c_int;
 
@@ -314,13 +406,13 @@ pub type CurlMvsprintf =
   unsafe extern "C" fn(buffer: *mut c_char, format: *const c_char, args: *mut c_void) -> c_int;
 
 // WebSocket API 函数类型
-pub type CurlWsMeta = unsafe extern "C" fn(handle: CurlHandle) -> *const c_void;
+pub type CurlWsMeta = unsafe extern "C" fn(handle: CurlHandle) -> *const CurlWsFrame;
 pub type CurlWsRecv = unsafe extern "C" fn(
   handle: CurlHandle,
   buffer: *mut c_void,
   buflen: usize,
   recv: *mut usize,
-  meta: *mut c_void,
+  meta: *mut *const CurlWsFrame,
 ) -> c_int;
 pub type CurlWsSend = unsafe extern "C" fn(
   handle: CurlHandle,
@@ -336,6 +428,33 @@ pub type CurlPushheaderByname =
   unsafe extern "C" fn(h: *mut c_void, name: *const c_char) -> *mut c_char;
 pub type CurlPushheaderBynum = unsafe extern "C" fn(h: *mut c_void, num: usize) -> *mut c_char;
 
+// 可选符号：较旧或配置不同的构建可能缺少，缺少时相关 API 返回错误而不是整体加载失败
+#[derive(Debug, Clone)]
+pub struct OptionalSymbol<T: 'static> {
+  pub name: &'static str,
+  symbol: Option<Symbol<'static, T>>,
+}
+
+impl<T> OptionalSymbol<T> {
+  fn load(lib: &'static Library, name: &'static str) -> Self {
+    let symbol = unsafe { lib.get(format!("{}\0", name).as_bytes()) }.ok();
+    OptionalSymbol { name, symbol }
+  }
+
+  pub fn is_loaded(&self) -> bool {
+    self.symbol.is_some()
+  }
+
+  pub fn get(&self) -> napi::Result<&Symbol<'static, T>> {
+    self.symbol.as_ref().ok_or_else(|| {
+      Error::new(
+        Status::GenericFailure,
+        format!("{} is not supported by loaded libcurl", self.name),
+      )
+    })
+  }
+}
+
 // 存储所有加载的函数 - 完整版本
 #[derive(Debug, Clone)]
 pub struct CurlFunctions {
@@ -348,19 +467,19 @@ pub struct CurlFunctions {
   pub easy_duphandle: Symbol<'static, CurlEasyDuphandle>,
   pub easy_reset: Symbol<'static, CurlEasyReset>,
   pub easy_strerror: Symbol<'static, CurlEasyStrerror>,
-  pub easy_impersonate: Symbol<'static, CurlEasyImpersonate>,
+  pub easy_impersonate: OptionalSymbol<CurlEasyImpersonate>,
   pub easy_escape: Symbol<'static, CurlEasyEscape>,
   pub easy_unescape: Symbol<'static, CurlEasyUnescape>,
-  pub easy_header: Symbol<'static, CurlEasyHeader>,
-  pub easy_nextheader: Symbol<'static, CurlEasyNextheader>,
-  pub easy_option_by_id: Symbol<'static, CurlEasyOptionById>,
-  pub easy_option_by_name: Symbol<'static, CurlEasyOptionByName>,
-  pub easy_option_next: Symbol<'static, CurlEasyOptionNext>,
+  pub easy_header: OptionalSymbol<CurlEasyHeader>,
+  pub easy_nextheader: OptionalSymbol<CurlEasyNextheader>,
+  pub easy_option_by_id: OptionalSymbol<CurlEasyOptionById>,
+  pub easy_option_by_name: OptionalSymbol<CurlEasyOptionByName>,
+  pub easy_option_next: OptionalSymbol<CurlEasyOptionNext>,
   pub easy_pause: Symbol<'static, CurlEasyPause>,
   pub easy_recv: Symbol<'static, CurlEasyRecv>,
   pub easy_send: Symbol<'static, CurlEasySend>,
-  pub easy_ssls_export: Symbol<'static, CurlEasySslsExport>,
-  pub easy_upkeep: Symbol<'static, CurlEasyUpkeep>,
+  pub easy_ssls_export: OptionalSymbol<CurlEasySslsExport>,
+  pub easy_upkeep: OptionalSymbol<CurlEasyUpkeep>,
 
   // Multi interface - 完整版本
   pub multi_init: Symbol<'static, CurlMultiInit>,
@@ -380,8 +499,8 @@ pub struct CurlFunctions {
   pub multi_timeout: Symbol<'static, CurlMultiTimeout>,
   pub multi_fdset: Symbol<'static, CurlMultiFdset>,
   pub multi_assign: Symbol<'static, CurlMultiAssign>,
-  pub multi_get_handles: Symbol<'static, CurlMultiGetHandles>,
-  pub multi_waitfds: Symbol<'static, CurlMultiWaitfds>,
+  pub multi_get_handles: OptionalSymbol<CurlMultiGetHandles>,
+  pub multi_waitfds: OptionalSymbol<CurlMultiWaitfds>,
 
   // Slist
   pub slist_append: Symbol<'static, CurlSlistAppend>,
@@ -418,14 +537,14 @@ pub struct CurlFunctions {
   pub url_dup: Symbol<'static, CurlUrlDup>,
   pub url_set: Symbol<'static, CurlUrlSet>,
   pub url_get: Symbol<'static, CurlUrlGet>,
-  pub url_strerror: Symbol<'static, CurlUrlStrerror>,
+  pub url_strerror: OptionalSymbol<CurlUrlStrerror>,
 
   // Global functions - 完整版本
   pub global_init: Symbol<'static, CurlGlobalInit>,
   pub global_init_mem: Symbol<'static, CurlGlobalInitMem>,
   pub global_cleanup: Symbol<'static, CurlGlobalCleanup>,
   pub global_sslset: Symbol<'static, CurlGlobalSslset>,
-  pub global_trace: Symbol<'static, CurlGlobalTrace>,
+  pub global_trace: OptionalSymbol<CurlGlobalTrace>,
   pub version: Symbol<'static, CurlVersion>,
   pub version_info: Symbol<'static, CurlVersionInfo>,
   pub escape: Symbol<'static, CurlEscape>,
@@ -455,15 +574,39 @@ pub struct CurlFunctions {
   pub mvsprintf: Symbol<'static, CurlMvsprintf>,
 
   // WebSocket API
-  pub ws_meta: Symbol<'static, CurlWsMeta>,
-  pub ws_recv: Symbol<'static, CurlWsRecv>,
-  pub ws_send: Symbol<'static, CurlWsSend>,
+  pub ws_meta: OptionalSymbol<CurlWsMeta>,
+  pub ws_recv: OptionalSymbol<CurlWsRecv>,
+  pub ws_send: OptionalSymbol<CurlWsSend>,
 
   // Push header 函数
   pub pushheader_byname: Symbol<'static, CurlPushheaderByname>,
   pub pushheader_bynum: Symbol<'static, CurlPushheaderBynum>,
 }
 
+impl CurlFunctions {
+  /// 各可选符号及其是否已加载
+  pub fn optional_symbols(&self) -> Vec<(&'static str, bool)> {
+    [
+      (self.easy_impersonate.name, self.easy_impersonate.is_loaded()),
+      (self.easy_header.name, self.easy_header.is_loaded()),
+      (self.easy_nextheader.name, self.easy_nextheader.is_loaded()),
+      (self.easy_option_by_id.name, self.easy_option_by_id.is_loaded()),
+      (self.easy_option_by_name.name, self.easy_option_by_name.is_loaded()),
+      (self.easy_option_next.name, self.easy_option_next.is_loaded()),
+      (self.easy_ssls_export.name, self.easy_ssls_export.is_loaded()),
+      (self.easy_upkeep.name, self.easy_upkeep.is_loaded()),
+      (self.multi_get_handles.name, self.multi_get_handles.is_loaded()),
+      (self.multi_waitfds.name, self.multi_waitfds.is_loaded()),
+      (self.url_strerror.name, self.url_strerror.is_loaded()),
+      (self.global_trace.name, self.global_trace.is_loaded()),
+      (self.ws_meta.name, self.ws_meta.is_loaded()),
+      (self.ws_recv.name, self.ws_recv.is_loaded()),
+      (self.ws_send.name, self.ws_send.is_loaded()),
+    ]
+    .to_vec()
+  }
+}
+
 // 实现 Send 和 Sync trait
 unsafe impl Send for CurlFunctions {}
 unsafe impl Sync for CurlFunctions {}
@@ -485,143 +628,162 @@ pub union CurlMsgData {
 pub fn load_curl_library() -> Result<&'static CurlFunctions, Box<dyn std::error::Error>> {
   let lib_path = get_lib_path().ok_or("lib path is not set")?;
 
-  CURL_FUNCTIONS.get_or_try_init(|| {
-    let lib = unsafe { Library::new(&lib_path)? };
-    let lib_static: &'static Library = Box::leak(Box::new(lib));
-
-    let functions = CurlFunctions {
-      // Easy interface - 完整版本
-      easy_init: unsafe { lib_static.get(b"curl_easy_init\0")? },
-      easy_cleanup: unsafe { lib_static.get(b"curl_easy_cleanup\0")? },
-      easy_setopt: unsafe { lib_static.get(b"curl_easy_setopt\0")? },
-      easy_perform: unsafe { lib_static.get(b"curl_easy_perform\0")? },
-      easy_getinfo: unsafe { lib_static.get(b"curl_easy_getinfo\0")? },
-      easy_duphandle: unsafe { lib_static.get(b"curl_easy_duphandle\0")? },
-      easy_reset: unsafe { lib_static.get(b"curl_easy_reset\0")? },
-      easy_strerror: unsafe { lib_static.get(b"curl_easy_strerror\0")? },
-      easy_impersonate: unsafe { lib_static.get(b"curl_easy_impersonate\0")? },
-      easy_escape: unsafe { lib_static.get(b"curl_easy_escape\0")? },
-      easy_unescape: unsafe { lib_static.get(b"curl_easy_unescape\0")? },
-      easy_header: unsafe { lib_static.get(b"curl_easy_header\0")? },
-      easy_nextheader: unsafe { lib_static.get(b"curl_easy_nextheader\0")? },
-      easy_option_by_id: unsafe { lib_static.get(b"curl_easy_option_by_id\0")? },
-      easy_option_by_name: unsafe { lib_static.get(b"curl_easy_option_by_name\0")? },
-      easy_option_next: unsafe { lib_static.get(b"curl_easy_option_next\0")? },
-      easy_pause: unsafe { lib_static.get(b"curl_easy_pause\0")? },
-      easy_recv: unsafe { lib_static.get(b"curl_easy_recv\0")? },
-      easy_send: unsafe { lib_static.get(b"curl_easy_send\0")? },
-      easy_ssls_export: unsafe { lib_static.get(b"curl_easy_ssls_export\0")? },
-      easy_upkeep: unsafe { lib_static.get(b"curl_easy_upkeep\0")? },
-
-      // Multi interface - 完整版本
-      multi_init: unsafe { lib_static.get(b"curl_multi_init\0")? },
-      multi_cleanup: unsafe { lib_static.get(b"curl_multi_cleanup\0")? },
-      multi_perform: unsafe { lib_static.get(b"curl_multi_perform\0")? },
-      multi_wait: unsafe { lib_static.get(b"curl_multi_wait\0")? },
-      multi_poll: unsafe { lib_static.get(b"curl_multi_poll\0")? },
-      multi_wakeup: unsafe { lib_static.get(b"curl_multi_wakeup\0")? },
-      multi_add_handle: unsafe { lib_static.get(b"curl_multi_add_handle\0")? },
-      multi_remove_handle: unsafe { lib_static.get(b"curl_multi_remove_handle\0")? },
-      multi_info_read: unsafe { lib_static.get(b"curl_multi_info_read\0")? },
-      multi_setopt: unsafe { lib_static.get(b"curl_multi_setopt\0")? },
-      multi_strerror: unsafe { lib_static.get(b"curl_multi_strerror\0")? },
-      multi_socket: unsafe { lib_static.get(b"curl_multi_socket\0")? },
-      multi_socket_action: unsafe { lib_static.get(b"curl_multi_socket_action\0")? },
-      multi_socket_all: unsafe { lib_static.get(b"curl_multi_socket_all\0")? },
-      multi_timeout: unsafe { lib_static.get(b"curl_multi_timeout\0")? },
-      multi_fdset: unsafe { lib_static.get(b"curl_multi_fdset\0")? },
-      multi_assign: unsafe { lib_static.get(b"curl_multi_assign\0")? },
-      multi_get_handles: unsafe { lib_static.get(b"curl_multi_get_handles\0")? },
-      multi_waitfds: unsafe { lib_static.get(b"curl_multi_waitfds\0")? },
-
-      // Slist
-      slist_append: unsafe { lib_static.get(b"curl_slist_append\0")? },
-      slist_free_all: unsafe { lib_static.get(b"curl_slist_free_all\0")? },
-
-      // MIME - 完整版本
-      mime_init: unsafe { lib_static.get(b"curl_mime_init\0")? },
-      mime_free: unsafe { lib_static.get(b"curl_mime_free\0")? },
-      mime_addpart: unsafe { lib_static.get(b"curl_mime_addpart\0")? },
-      mime_name: unsafe { lib_static.get(b"curl_mime_name\0")? },
-      mime_data: unsafe { lib_static.get(b"curl_mime_data\0")? },
-      mime_data_cb: unsafe { lib_static.get(b"curl_mime_data_cb\0")? },
-      mime_encoder: unsafe { lib_static.get(b"curl_mime_encoder\0")? },
-      mime_filedata: unsafe { lib_static.get(b"curl_mime_filedata\0")? },
-      mime_filename: unsafe { lib_static.get(b"curl_mime_filename\0")? },
-      mime_headers: unsafe { lib_static.get(b"curl_mime_headers\0")? },
-      mime_subparts: unsafe { lib_static.get(b"curl_mime_subparts\0")? },
-      mime_type: unsafe { lib_static.get(b"curl_mime_type\0")? },
-
-      // Form API
-      formadd: unsafe { lib_static.get(b"curl_formadd\0")? },
-      formfree: unsafe { lib_static.get(b"curl_formfree\0")? },
-      formget: unsafe { lib_static.get(b"curl_formget\0")? },
-
-      // Share API
-      share_init: unsafe { lib_static.get(b"curl_share_init\0")? },
-      share_cleanup: unsafe { lib_static.get(b"curl_share_cleanup\0")? },
-      share_setopt: unsafe { lib_static.get(b"curl_share_setopt\0")? },
-      share_strerror: unsafe { lib_static.get(b"curl_share_strerror\0")? },
-
-      // URL API - 完整版本
-      url: unsafe { lib_static.get(b"curl_url\0")? },
-      url_cleanup: unsafe { lib_static.get(b"curl_url_cleanup\0")? },
-      url_dup: unsafe { lib_static.get(b"curl_url_dup\0")? },
-      url_set: unsafe { lib_static.get(b"curl_url_set\0")? },
-      url_get: unsafe { lib_static.get(b"curl_url_get\0")? },
-      url_strerror: unsafe { lib_static.get(b"curl_url_strerror\0")? },
-
-      // Global functions - 完整版本
-      global_init: unsafe { lib_static.get(b"curl_global_init\0")? },
-      global_init_mem: unsafe { lib_static.get(b"curl_global_init_mem\0")? },
-      global_cleanup: unsafe { lib_static.get(b"curl_global_cleanup\0")? },
-      global_sslset: unsafe { lib_static.get(b"curl_global_sslset\0")? },
-      global_trace: unsafe { lib_static.get(b"curl_global_trace\0")? },
-      version: unsafe { lib_static.get(b"curl_version\0")? },
-      version_info: unsafe { lib_static.get(b"curl_version_info\0")? },
-      escape: unsafe { lib_static.get(b"curl_escape\0")? },
-      unescape: unsafe { lib_static.get(b"curl_unescape\0")? },
-      free: unsafe { lib_static.get(b"curl_free\0")? },
-
-      // 字符串处理函数
-      strequal: unsafe { lib_static.get(b"curl_strequal\0")? },
-      strnequal: unsafe { lib_static.get(b"curl_strnequal\0")? },
-
-      // 时间函数
-      getdate: unsafe { lib_static.get(b"curl_getdate\0")? },
-
-      // 环境函数
-      getenv: unsafe { lib_static.get(b"curl_getenv\0")? },
-
-      // printf 系列函数
-      maprintf: unsafe { lib_static.get(b"curl_maprintf\0")? },
-      mfprintf: unsafe { lib_static.get(b"curl_mfprintf\0")? },
-      mprintf: unsafe { lib_static.get(b"curl_mprintf\0")? },
-      msnprintf: unsafe { lib_static.get(b"curl_msnprintf\0")? },
-      msprintf: unsafe { lib_static.get(b"curl_msprintf\0")? },
-      mvaprintf: unsafe { lib_static.get(b"curl_mvaprintf\0")? },
-      mvfprintf: unsafe { lib_static.get(b"curl_mvfprintf\0")? },
-      mvprintf: unsafe { lib_static.get(b"curl_mvprintf\0")? },
-      mvsnprintf: unsafe { lib_static.get(b"curl_mvsnprintf\0")? },
-      mvsprintf: unsafe { lib_static.get(b"curl_mvsprintf\0")? },
-
-      // WebSocket API
-      ws_meta: unsafe { lib_static.get(b"curl_ws_meta\0")? },
-      ws_recv: unsafe { lib_static.get(b"curl_ws_recv\0")? },
-      ws_send: unsafe { lib_static.get(b"curl_ws_send\0")? },
-
-      // Push header 函数
-      pushheader_byname: unsafe { lib_static.get(b"curl_pushheader_byname\0")? },
-      pushheader_bynum: unsafe { lib_static.get(b"curl_pushheader_bynum\0")? },
-    };
-
-    Ok(functions)
-  })
+  CURL_FUNCTIONS
+    .get_or_try_init(|| load_library_at(&lib_path))
+    .copied()
+}
+
+// 加载指定路径的库，已加载过的路径直接复用
+pub fn load_library_at(path: &str) -> Result<&'static CurlFunctions, Box<dyn std::error::Error>> {
+  // 相对路径、符号链接等指向同一文件时共用一份；无法解析的名称交给动态链接器按原样查找
+  let path = std::fs::canonicalize(path)
+    .map(|path| path.to_string_lossy().into_owned())
+    .unwrap_or_else(|_| path.to_string());
+  let mut libraries = LIBRARIES.lock().map_err(|_| "library cache is poisoned")?;
+  if let Some(functions) = libraries.get(&path) {
+    return Ok(functions);
+  }
+  let functions: &'static CurlFunctions = Box::leak(Box::new(load_functions(&path)?));
+  libraries.insert(path, functions);
+  Ok(functions)
+}
+
+fn load_functions(lib_path: &str) -> Result<CurlFunctions, Box<dyn std::error::Error>> {
+  let lib = unsafe { Library::new(lib_path)? };
+  let lib_static: &'static Library = Box::leak(Box::new(lib));
+
+  let functions = CurlFunctions {
+    // Easy interface - 完整版本
+    easy_init: unsafe { lib_static.get(b"curl_easy_init\0")? },
+    easy_cleanup: unsafe { lib_static.get(b"curl_easy_cleanup\0")? },
+    easy_setopt: unsafe { lib_static.get(b"curl_easy_setopt\0")? },
+    easy_perform: unsafe { lib_static.get(b"curl_easy_perform\0")? },
+    easy_getinfo: unsafe { lib_static.get(b"curl_easy_getinfo\0")? },
+    easy_duphandle: unsafe { lib_static.get(b"curl_easy_duphandle\0")? },
+    easy_reset: unsafe { lib_static.get(b"curl_easy_reset\0")? },
+    easy_strerror: unsafe { lib_static.get(b"curl_easy_strerror\0")? },
+    easy_impersonate: OptionalSymbol::load(lib_static, "curl_easy_impersonate"),
+    easy_escape: unsafe { lib_static.get(b"curl_easy_escape\0")? },
+    easy_unescape: unsafe { lib_static.get(b"curl_easy_unescape\0")? },
+    easy_header: OptionalSymbol::load(lib_static, "curl_easy_header"),
+    easy_nextheader: OptionalSymbol::load(lib_static, "curl_easy_nextheader"),
+    easy_option_by_id: OptionalSymbol::load(lib_static, "curl_easy_option_by_id"),
+    easy_option_by_name: OptionalSymbol::load(lib_static, "curl_easy_option_by_name"),
+    easy_option_next: OptionalSymbol::load(lib_static, "curl_easy_option_next"),
+    easy_pause: unsafe { lib_static.get(b"curl_easy_pause\0")? },
+    easy_recv: unsafe { lib_static.get(b"curl_easy_recv\0")? },
+    easy_send: unsafe { lib_static.get(b"curl_easy_send\0")? },
+    easy_ssls_export: OptionalSymbol::load(lib_static, "curl_easy_ssls_export"),
+    easy_upkeep: OptionalSymbol::load(lib_static, "curl_easy_upkeep"),
+
+    // Multi interface - 完整版本
+    multi_init: unsafe { lib_static.get(b"curl_multi_init\0")? },
+    multi_cleanup: unsafe { lib_static.get(b"curl_multi_cleanup\0")? },
+    multi_perform: unsafe { lib_static.get(b"curl_multi_perform\0")? },
+    multi_wait: unsafe { lib_static.get(b"curl_multi_wait\0")? },
+    multi_poll: unsafe { lib_static.get(b"curl_multi_poll\0")? },
+    multi_wakeup: unsafe { lib_static.get(b"curl_multi_wakeup\0")? },
+    multi_add_handle: unsafe { lib_static.get(b"curl_multi_add_handle\0")? },
+    multi_remove_handle: unsafe { lib_static.get(b"curl_multi_remove_handle\0")? },
+    multi_info_read: unsafe { lib_static.get(b"curl_multi_info_read\0")? },
+    multi_setopt: unsafe { lib_static.get(b"curl_multi_setopt\0")? },
+    multi_strerror: unsafe { lib_static.get(b"curl_multi_strerror\0")? },
+    multi_socket: unsafe { lib_static.get(b"curl_multi_socket\0")? },
+    multi_socket_action: unsafe { lib_static.get(b"curl_multi_socket_action\0")? },
+    multi_socket_all: unsafe { lib_static.get(b"curl_multi_socket_all\0")? },
+    multi_timeout: unsafe { lib_static.get(b"curl_multi_timeout\0")? },
+    multi_fdset: unsafe { lib_static.get(b"curl_multi_fdset\0")? },
+    multi_assign: unsafe { lib_static.get(b"curl_multi_assign\0")? },
+    multi_get_handles: OptionalSymbol::load(lib_static, "curl_multi_get_handles"),
+    multi_waitfds: OptionalSymbol::load(lib_static, "curl_multi_waitfds"),
+
+    // Slist
+    slist_append: unsafe { lib_static.get(b"curl_slist_append\0")? },
+    slist_free_all: unsafe { lib_static.get(b"curl_slist_free_all\0")? },
+
+    // MIME - 完整版本
+    mime_init: unsafe { lib_static.get(b"curl_mime_init\0")? },
+    mime_free: unsafe { lib_static.get(b"curl_mime_free\0")? },
+    mime_addpart: unsafe { lib_static.get(b"curl_mime_addpart\0")? },
+    mime_name: unsafe { lib_static.get(b"curl_mime_name\0")? },
+    mime_data: unsafe { lib_static.get(b"curl_mime_data\0")? },
+    mime_data_cb: unsafe { lib_static.get(b"curl_mime_data_cb\0")? },
+    mime_encoder: unsafe { lib_static.get(b"curl_mime_encoder\0")? },
+    mime_filedata: unsafe { lib_static.get(b"curl_mime_filedata\0")? },
+    mime_filename: unsafe { lib_static.get(b"curl_mime_filename\0")? },
+    mime_headers: unsafe { lib_static.get(b"curl_mime_headers\0")? },
+    mime_subparts: unsafe { lib_static.get(b"curl_mime_subparts\0")? },
+    mime_type: unsafe { lib_static.get(b"curl_mime_type\0")? },
+
+    // Form API
+    formadd: unsafe { lib_static.get(b"curl_formadd\0")? },
+    formfree: unsafe { lib_static.get(b"curl_formfree\0")? },
+    formget: unsafe { lib_static.get(b"curl_formget\0")? },
+
+    // Share API
+    share_init: unsafe { lib_static.get(b"curl_share_init\0")? },
+    share_cleanup: unsafe { lib_static.get(b"curl_share_cleanup\0")? },
+    share_setopt: unsafe { lib_static.get(b"curl_share_setopt\0")? },
+    share_strerror: unsafe { lib_static.get(b"curl_share_strerror\0")? },
+
+    // URL API - 完整版本
+    url: unsafe { lib_static.get(b"curl_url\0")? },
+    url_cleanup: unsafe { lib_static.get(b"curl_url_cleanup\0")? },
+    url_dup: unsafe { lib_static.get(b"curl_url_dup\0")? },
+    url_set: unsafe { lib_static.get(b"curl_url_set\0")? },
+    url_get: unsafe { lib_static.get(b"curl_url_get\0")? },
+    url_strerror: OptionalSymbol::load(lib_static, "curl_url_strerror"),
+
+    // Global functions - 完整版本
+    global_init: unsafe { lib_static.get(b"curl_global_init\0")? },
+    global_init_mem: unsafe { lib_static.get(b"curl_global_init_mem\0")? },
+    global_cleanup: unsafe { lib_static.get(b"curl_global_cleanup\0")? },
+    global_sslset: unsafe { lib_static.get(b"curl_global_sslset\0")? },
+    global_trace: OptionalSymbol::load(lib_static, "curl_global_trace"),
+    version: unsafe { lib_static.get(b"curl_version\0")? },
+    version_info: unsafe { lib_static.get(b"curl_version_info\0")? },
+    escape: unsafe { lib_static.get(b"curl_escape\0")? },
+    unescape: unsafe { lib_static.get(b"curl_unescape\0")? },
+    free: unsafe { lib_static.get(b"curl_free\0")? },
+
+    // 字符串处理函数
+    strequal: unsafe { lib_static.get(b"curl_strequal\0")? },
+    strnequal: unsafe { lib_static.get(b"curl_strnequal\0")? },
+
+    // 时间函数
+    getdate: unsafe { lib_static.get(b"curl_getdate\0")? },
+
+    // 环境函数
+    getenv: unsafe { lib_static.get(b"curl_getenv\0")? },
+
+    // printf 系列函数
+    maprintf: unsafe { lib_static.get(b"curl_maprintf\0")? },
+    mfprintf: unsafe { lib_static.get(b"curl_mfprintf\0")? },
+    mprintf: unsafe { lib_static.get(b"curl_mprintf\0")? },
+    msnprintf: unsafe { lib_static.get(b"curl_msnprintf\0")? },
+    msprintf: unsafe { lib_static.get(b"curl_msprintf\0")? },
+    mvaprintf: unsafe { lib_static.get(b"curl_mvaprintf\0")? },
+    mvfprintf: unsafe { lib_static.get(b"curl_mvfprintf\0")? },
+    mvprintf: unsafe { lib_static.get(b"curl_mvprintf\0")? },
+    mvsnprintf: unsafe { lib_static.get(b"curl_mvsnprintf\0")? },
+    mvsprintf: unsafe { lib_static.get(b"curl_mvsprintf\0")? },
+
+    // WebSocket API
+    ws_meta: OptionalSymbol::load(lib_static, "curl_ws_meta"),
+    ws_recv: OptionalSymbol::load(lib_static, "curl_ws_recv"),
+    ws_send: OptionalSymbol::load(lib_static, "curl_ws_send"),
+
+    // Push header 函数
+    pushheader_byname: unsafe { lib_static.get(b"curl_pushheader_byname\0")? },
+    pushheader_bynum: unsafe { lib_static.get(b"curl_pushheader_bynum\0")? },
+  };
+
+  Ok(functions)
 }
 
 // 获取已加载的函数实例（如果已经加载）
 pub fn get_curl_functions() -> Option<&'static CurlFunctions> {
-  CURL_FUNCTIONS.get()
+  CURL_FUNCTIONS.get().copied()
 }
 
 // 检查库是否已加载
@@ -629,16 +791,30 @@ pub fn is_library_loaded() -> bool {
   CURL_FUNCTIONS.get().is_some()
 }
 
+pub fn napi_load_library_at(path: &str) -> napi::Result<&'static CurlFunctions> {
+  load_library_at(path).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to load libcurl library. Path: {}, Error: {}", path, e),
+    )
+  })
+}
+
+// 两个句柄是否来自同一个库，不同库的句柄不能混用
+pub fn same_library(a: &'static CurlFunctions, b: &'static CurlFunctions) -> bool {
+  std::ptr::eq(a, b)
+}
+
 pub fn napi_load_library() -> napi::Result<&'static CurlFunctions> {
-  load_curl_library().or_else(|e| {
-    Err(Error::new(
+  load_curl_library().map_err(|e| {
+    Error::new(
       Status::GenericFailure,
       format!(
         "Failed to load @tocha688/libcurl library. Path: {}, Error: {}",
         get_lib_path().unwrap_or("unknown path".to_string()),
         e
       ),
-    ))
+    )
   })
 }
 
@@ -1,35 +1,112 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi::bindgen_prelude::Unknown;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::Status;
+use napi_derive::napi;
+use once_cell::sync::Lazy;
 
 /// 日志级别
+#[napi]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
-    Debug,
-    Info,
-    Warn,
-    Error,
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+impl LogLevel {
+    fn from_u8(value: u8) -> LogLevel {
+        match value {
+            0 => LogLevel::Debug,
+            1 => LogLevel::Info,
+            2 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+/// 交给 JS 日志接收函数的一条日志
+#[napi(object)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    /// 产生日志的模块，如 Curl、CurlMulti
+    pub module: String,
+    /// 相关句柄的地址，与 Curl.id() 一致
+    pub handle: Option<String>,
+    pub message: String,
+    /// 毫秒时间戳，可直接传给 new Date()
+    pub timestamp: f64,
 }
 
+/// 弱引用的线程安全函数，不会阻止进程退出
+pub type LogSink = ThreadsafeFunction<LogRecord, Unknown<'static>, LogRecord, Status, true, true>;
+
 /// 全局启用/禁用日志的标志
 static LOGGING_ENABLED: AtomicBool = AtomicBool::new(false);
 /// 当前日志级别
-static mut CURRENT_LOG_LEVEL: LogLevel = LogLevel::Info;
+static CURRENT_LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+/// 按模块覆盖的日志级别，未设置时跳过查表
+static HAS_MODULE_LEVELS: AtomicBool = AtomicBool::new(false);
+static MODULE_LEVELS: Lazy<RwLock<HashMap<String, LogLevel>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+/// JS 日志接收函数，未设置时输出到 stdout/stderr
+static LOG_SINK: Lazy<RwLock<Option<Arc<LogSink>>>> = Lazy::new(|| RwLock::new(None));
 
 /// 启用日志输出
+#[napi]
 pub fn enable_logging(enable: bool) {
     LOGGING_ENABLED.store(enable, Ordering::SeqCst);
 }
 
 /// 设置日志级别
+#[napi]
 pub fn set_log_level(level: LogLevel) {
-    unsafe {
-        CURRENT_LOG_LEVEL = level;
-    }
+    CURRENT_LOG_LEVEL.store(level as u8, Ordering::SeqCst);
 }
 
 /// 获取当前日志级别
+#[napi]
 pub fn get_log_level() -> LogLevel {
-    unsafe {
-        CURRENT_LOG_LEVEL
+    LogLevel::from_u8(CURRENT_LOG_LEVEL.load(Ordering::SeqCst))
+}
+
+/// 单独设置某个模块的日志级别，level 为空时恢复使用全局级别
+#[napi]
+pub fn set_module_log_level(module: String, level: Option<LogLevel>) {
+    if let Ok(mut levels) = MODULE_LEVELS.write() {
+        match level {
+            Some(level) => {
+                levels.insert(module, level);
+            }
+            None => {
+                levels.remove(&module);
+            }
+        }
+        HAS_MODULE_LEVELS.store(!levels.is_empty(), Ordering::SeqCst);
+    }
+}
+
+/// 设置日志接收函数，传空恢复输出到 stdout/stderr
+///
+/// 接收函数不会阻止进程退出，退出前尚未投递的日志会被丢弃
+#[napi(ts_args_type = "sink?: ((err: Error | null, record: LogRecord) => any) | undefined | null")]
+pub fn set_log_sink(sink: Option<LogSink>) {
+    if let Ok(mut current) = LOG_SINK.write() {
+        *current = sink.map(Arc::new);
     }
 }
 
@@ -38,82 +115,99 @@ pub fn is_logging_enabled() -> bool {
     LOGGING_ENABLED.load(Ordering::SeqCst)
 }
 
-/// 判断指定级别的日志是否应该输出
-pub fn should_log(level: LogLevel) -> bool {
+/// 判断指定模块、级别的日志是否应该输出
+pub fn should_log(module: &str, level: LogLevel) -> bool {
     if !is_logging_enabled() {
         return false;
     }
 
-    match (unsafe { CURRENT_LOG_LEVEL }, level) {
-        (LogLevel::Debug, _) => true,
-        (LogLevel::Info, LogLevel::Info | LogLevel::Warn | LogLevel::Error) => true,
-        (LogLevel::Warn, LogLevel::Warn | LogLevel::Error) => true,
-        (LogLevel::Error, LogLevel::Error) => true,
-        _ => false,
-    }
-}
-
-/// 记录调试日志
-pub fn log_debug(module: &str, message: &str) {
-    if should_log(LogLevel::Debug) {
-        println!("[DEBUG][{}] {}", module, message);
+    let mut threshold = get_log_level();
+    if HAS_MODULE_LEVELS.load(Ordering::SeqCst) {
+        if let Some(module_level) = MODULE_LEVELS
+            .read()
+            .ok()
+            .and_then(|levels| levels.get(module).copied())
+        {
+            threshold = module_level;
+        }
     }
+    level as u8 >= threshold as u8
 }
 
-/// 记录信息日志
-pub fn log_info(module: &str, message: &str) {
-    if should_log(LogLevel::Info) {
-        println!("[INFO][{}] {}", module, message);
+/// 记录一条日志，有接收函数时交给 JS，否则输出到 stdout/stderr
+pub fn log(level: LogLevel, module: &str, handle: Option<String>, message: String) {
+    let sink = LOG_SINK.read().ok().and_then(|sink| sink.clone());
+    if let Some(sink) = sink {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+            .unwrap_or_default();
+        sink.call(
+            Ok(LogRecord {
+                level,
+                module: module.to_string(),
+                handle,
+                message,
+                timestamp,
+            }),
+            ThreadsafeFunctionCallMode::NonBlocking,
+        );
+        return;
     }
-}
 
-/// 记录警告日志
-pub fn log_warn(module: &str, message: &str) {
-    if should_log(LogLevel::Warn) {
-        println!("[WARN][{}] {}", module, message);
+    let handle = handle.map(|handle| format!("[{}]", handle)).unwrap_or_default();
+    match level {
+        LogLevel::Error => eprintln!("[{}][{}]{} {}", level.label(), module, handle, message),
+        _ => println!("[{}][{}]{} {}", level.label(), module, handle, message),
     }
 }
 
-/// 记录错误日志
-pub fn log_error(module: &str, message: &str) {
-    if should_log(LogLevel::Error) {
-        eprintln!("[ERROR][{}] {}", module, message);
-    }
+// 便捷宏，支持格式化输出；可用 `handle = id;` 附带相关句柄
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $module:expr, $handle:expr, $($arg:tt)*) => {
+        if $crate::logger::should_log($module, $level) {
+            $crate::logger::log($level, $module, $handle, format!($($arg)*));
+        }
+    };
 }
 
-// 便捷宏，支持格式化输出
 #[macro_export]
 macro_rules! log_debug {
+    ($module:expr, handle = $handle:expr; $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Debug, $module, Some(($handle).to_string()), $($arg)*)
+    };
     ($module:expr, $($arg:tt)*) => {
-        if $crate::logger::should_log($crate::logger::LogLevel::Debug) {
-            $crate::logger::log_debug($module, &format!($($arg)*));
-        }
+        $crate::log_at!($crate::logger::LogLevel::Debug, $module, None, $($arg)*)
     };
 }
 
 #[macro_export]
-macro_rules! log_info{
+macro_rules! log_info {
+    ($module:expr, handle = $handle:expr; $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Info, $module, Some(($handle).to_string()), $($arg)*)
+    };
     ($module:expr, $($arg:tt)*) => {
-        if $crate::logger::should_log($crate::logger::LogLevel::Info) {
-            $crate::logger::log_info($module, &format!($($arg)*));
-        }
+        $crate::log_at!($crate::logger::LogLevel::Info, $module, None, $($arg)*)
     };
 }
 
 #[macro_export]
-macro_rules! log_warn{
+macro_rules! log_warn {
+    ($module:expr, handle = $handle:expr; $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Warn, $module, Some(($handle).to_string()), $($arg)*)
+    };
     ($module:expr, $($arg:tt)*) => {
-        if $crate::logger::should_log($crate::logger::LogLevel::Warn) {
-            $crate::logger::log_warn($module, &format!($($arg)*));
-        }
+        $crate::log_at!($crate::logger::LogLevel::Warn, $module, None, $($arg)*)
     };
 }
 
 #[macro_export]
-macro_rules! log_error{
+macro_rules! log_error {
+    ($module:expr, handle = $handle:expr; $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Error, $module, Some(($handle).to_string()), $($arg)*)
+    };
     ($module:expr, $($arg:tt)*) => {
-        if $crate::logger::should_log($crate::logger::LogLevel::Error) {
-            $crate::logger::log_error($module, &format!($($arg)*));
-        }
+        $crate::log_at!($crate::logger::LogLevel::Error, $module, None, $($arg)*)
     };
-}
\ No newline at end of file
+}
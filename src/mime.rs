@@ -0,0 +1,312 @@
+//! multipart/form-data 构建，基于 curl_mime_* API
+
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::ThreadsafeFunction;
+use napi::{Either, Error, Result, Status};
+use napi_derive::napi;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::api::easy_strerror;
+use crate::curl::Curl;
+use crate::loader::{CurlFunctions, CurlHandle, CurlMime as RawMime, CurlMimepart, ReadCallback};
+use crate::log_info;
+use crate::stream::{stream_read, TransferControl, UploadStream, DEFAULT_HIGH_WATER_MARK};
+
+// 单个 part 的描述
+#[napi(object)]
+pub struct MimePartOptions {
+  /// 表单字段名
+  pub name: Option<String>,
+  /// 内存数据，与 file 二选一
+  pub data: Option<Either<String, Buffer>>,
+  /// 文件路径，传输时由 libcurl 读取
+  pub file: Option<String>,
+  /// 远端看到的文件名，默认取 file 的文件名
+  pub filename: Option<String>,
+  pub content_type: Option<String>,
+  /// 额外的 part 头部，如 "Content-ID: <abc>"
+  pub headers: Option<Vec<String>>,
+  /// 传输编码：binary、8bit、7bit、base64、quoted-printable
+  pub encoder: Option<String>,
+}
+
+// mime 句柄及其依赖的数据，由 CurlMime 与挂载它的 Curl 共同持有，最后一个持有者释放
+pub struct MimeData {
+  handle: RawMime,
+  lib: &'static CurlFunctions,
+  // 创建时所属的 easy handle，流式 part 的暂停恢复依赖它
+  easy: CurlHandle,
+  control: Arc<TransferControl>,
+  // 流式 part 的数据源，地址在传输期间必须保持稳定
+  streams: Mutex<Vec<Arc<UploadStream>>>,
+  // 作为 subparts 挂载的子 mime
+  children: Mutex<Vec<Arc<MimeData>>>,
+  // 已挂载为父 mime 的 subparts，由父 mime 负责释放
+  attached: AtomicBool,
+}
+
+unsafe impl Send for MimeData {}
+unsafe impl Sync for MimeData {}
+
+impl MimeData {
+  pub fn handle(&self) -> RawMime {
+    self.handle
+  }
+
+  pub fn easy(&self) -> CurlHandle {
+    self.easy
+  }
+
+  /// 是否包含流式 part（含子 mime）
+  pub fn has_streams(&self) -> bool {
+    self.streams.lock().map(|s| !s.is_empty()).unwrap_or(false)
+      || self
+        .children
+        .lock()
+        .map(|c| c.iter().any(|child| child.has_streams()))
+        .unwrap_or(false)
+  }
+}
+
+impl Drop for MimeData {
+  fn drop(&mut self) {
+    if !self.attached.load(Ordering::SeqCst) && !self.handle.is_null() {
+      log_info!("CurlMime", "mime_free");
+      unsafe {
+        (self.lib.mime_free)(self.handle);
+      }
+    }
+  }
+}
+
+#[napi]
+pub struct CurlMime {
+  data: Arc<MimeData>,
+}
+
+#[napi]
+impl CurlMime {
+  #[napi(constructor)]
+  pub fn new(curl: &Curl) -> Result<Self> {
+    curl.check_close()?;
+    let lib = curl.lib();
+    let easy = curl.get_handle();
+    let handle = unsafe { (lib.mime_init)(easy) };
+    if handle.is_null() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Failed to initialize mime handle",
+      ));
+    }
+    Ok(CurlMime {
+      data: Arc::new(MimeData {
+        handle,
+        lib,
+        easy,
+        control: curl.control(),
+        streams: Mutex::new(Vec::new()),
+        children: Mutex::new(Vec::new()),
+        attached: AtomicBool::new(false),
+      }),
+    })
+  }
+
+  /// 添加文本、Buffer 或文件 part
+  #[napi]
+  pub fn add_part(&self, options: MimePartOptions) -> Result<()> {
+    let part = self.new_part()?;
+    self.apply(part, options)
+  }
+
+  /// 添加嵌套的 multipart/mixed part，sub 此后由当前 mime 管理，不能再次挂载
+  #[napi]
+  pub fn add_subparts(&self, sub: &CurlMime, options: Option<MimePartOptions>) -> Result<()> {
+    if Arc::ptr_eq(&self.data, &sub.data) {
+      return Err(Error::from_reason("Cannot attach a mime to itself"));
+    }
+    sub.check_detached()?;
+    let part = self.new_part()?;
+    self.check(unsafe { (self.data.lib.mime_subparts)(part, sub.data.handle) })?;
+    sub.data.attached.store(true, Ordering::SeqCst);
+    self
+      .data
+      .children
+      .lock()
+      .map_err(|_| Error::from_reason("Mime is poisoned"))?
+      .push(sub.data.clone());
+    if let Some(options) = options {
+      self.apply(part, options)?;
+    }
+    Ok(())
+  }
+
+  /// 添加流式 part，数据通过 write_part 写入、end_part 结束，返回 part 序号
+  ///
+  /// size 未知时整个请求使用 chunked 编码；write_part 返回 false 时应等待 on_drain 回调
+  #[napi]
+  pub fn add_stream_part(
+    &self,
+    options: MimePartOptions,
+    size: Option<i64>,
+    on_drain: Option<Arc<ThreadsafeFunction<()>>>,
+    high_water_mark: Option<u32>,
+  ) -> Result<u32> {
+    if options.data.is_some() || options.file.is_some() {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "Stream part cannot have data or file",
+      ));
+    }
+    let high_water_mark = high_water_mark
+      .map(|v| v as usize)
+      .unwrap_or(DEFAULT_HIGH_WATER_MARK)
+      .max(1);
+    let stream = Arc::new(UploadStream::from_queue(
+      self.data.control.clone(),
+      size,
+      high_water_mark,
+      on_drain,
+    ));
+    let part = self.new_part()?;
+    self.check(unsafe {
+      (self.data.lib.mime_data_cb)(
+        part,
+        size.unwrap_or(-1),
+        stream_read as ReadCallback,
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        Arc::as_ptr(&stream) as *mut c_void,
+      )
+    })?;
+    self.apply(part, options)?;
+    let mut streams = self
+      .data
+      .streams
+      .lock()
+      .map_err(|_| Error::from_reason("Mime is poisoned"))?;
+    streams.push(stream);
+    Ok((streams.len() - 1) as u32)
+  }
+
+  /// 向流式 part 写入数据，返回 false 表示缓冲已满
+  #[napi]
+  pub fn write_part(&self, index: u32, chunk: Buffer) -> Result<bool> {
+    self.with_stream(index, |stream| stream.write(&chunk))
+  }
+
+  /// 结束流式 part
+  #[napi]
+  pub fn end_part(&self, index: u32) -> Result<()> {
+    self.with_stream(index, |stream| stream.end())
+  }
+
+  pub fn data(&self) -> Arc<MimeData> {
+    self.data.clone()
+  }
+
+  pub fn check_detached(&self) -> Result<()> {
+    if self.data.attached.load(Ordering::SeqCst) {
+      return Err(Error::from_reason("Mime is already attached as subparts"));
+    }
+    Ok(())
+  }
+
+  fn new_part(&self) -> Result<CurlMimepart> {
+    self.check_detached()?;
+    let part = unsafe { (self.data.lib.mime_addpart)(self.data.handle) };
+    if part.is_null() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Failed to add mime part",
+      ));
+    }
+    Ok(part)
+  }
+
+  fn apply(&self, part: CurlMimepart, options: MimePartOptions) -> Result<()> {
+    let lib = self.data.lib;
+    if let Some(name) = options.name {
+      let name = c_string(name)?;
+      self.check(unsafe { (lib.mime_name)(part, name.as_ptr()) })?;
+    }
+    match (options.data, options.file) {
+      (Some(_), Some(_)) => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          "Mime part cannot have both data and file",
+        ))
+      }
+      (Some(data), None) => {
+        // curl_mime_data 会复制数据
+        let bytes = match data {
+          Either::A(text) => text.into_bytes(),
+          Either::B(buffer) => buffer.to_vec(),
+        };
+        self.check(unsafe { (lib.mime_data)(part, bytes.as_ptr() as *const _, bytes.len()) })?;
+      }
+      (None, Some(file)) => {
+        let file = c_string(file)?;
+        self.check(unsafe { (lib.mime_filedata)(part, file.as_ptr()) })?;
+      }
+      (None, None) => {}
+    }
+    if let Some(filename) = options.filename {
+      let filename = c_string(filename)?;
+      self.check(unsafe { (lib.mime_filename)(part, filename.as_ptr()) })?;
+    }
+    if let Some(content_type) = options.content_type {
+      let content_type = c_string(content_type)?;
+      self.check(unsafe { (lib.mime_type)(part, content_type.as_ptr()) })?;
+    }
+    if let Some(encoder) = options.encoder {
+      let encoder = c_string(encoder)?;
+      self.check(unsafe { (lib.mime_encoder)(part, encoder.as_ptr()) })?;
+    }
+    if let Some(headers) = options.headers {
+      let mut list = std::ptr::null_mut();
+      for header in headers {
+        let header = c_string(header)?;
+        list = unsafe { (lib.slist_append)(list, header.as_ptr()) };
+      }
+      // take_ownership=1：链表随 part 一起释放
+      self.check(unsafe { (lib.mime_headers)(part, list, 1) })?;
+    }
+    Ok(())
+  }
+
+  fn with_stream<T>(
+    &self,
+    index: u32,
+    f: impl FnOnce(&UploadStream) -> std::result::Result<T, &'static str>,
+  ) -> Result<T> {
+    let streams = self
+      .data
+      .streams
+      .lock()
+      .map_err(|_| Error::from_reason("Mime is poisoned"))?;
+    let Some(stream) = streams.get(index as usize) else {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!("Stream part {} does not exist", index),
+      ));
+    };
+    f(stream).map_err(Error::from_reason)
+  }
+
+  fn check(&self, code: c_int) -> Result<()> {
+    if code != 0 {
+      return Err(Error::new(
+        Status::GenericFailure,
+        format!("failed with code: {} message:{}", code, easy_strerror(self.data.lib, code)),
+      ));
+    }
+    Ok(())
+  }
+}
+
+fn c_string(value: String) -> Result<CString> {
+  CString::new(value).map_err(|_| Error::new(Status::InvalidArg, "Invalid mime string"))
+}
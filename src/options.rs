@@ -0,0 +1,237 @@
+//! easy 选项元数据：查询选项的值类型以校验 setOption 的参数，并列出已加载的 libcurl 支持的选项
+
+use std::ffi::{c_int, c_uint, c_void, CStr, CString};
+
+use napi::bindgen_prelude::{Buffer, Either5};
+use napi::{Either, Error, Result, Status};
+use napi_derive::napi;
+
+use crate::{
+  constants::{CurlOpt, CurlOptType},
+  loader::{napi_load_library, CurlEasyOption, CurlFunctions},
+};
+
+// CURLOPTTYPE_* 区间的起点
+const CURLOPTTYPE_OBJECTPOINT: c_int = 10000;
+const CURLOPTTYPE_FUNCTIONPOINT: c_int = 20000;
+const CURLOPTTYPE_OFF_T: c_int = 30000;
+const CURLOPTTYPE_BLOB: c_int = 40000;
+
+/// setOption 接受的值
+pub type CurlOptValue = Either5<String, i64, bool, Vec<String>, Buffer>;
+
+/// 查询选项的值类型，libcurl 未收录时按 CURLOPTTYPE 区间推断
+pub fn option_type(lib: &'static CurlFunctions, id: c_int, value: &CurlOptValue) -> CurlOptType {
+  // 指针区间同时包含字符串与链表，只能依据传入的值区分
+  known_type(lib, id).unwrap_or_else(|| infer_type(id, matches!(value, Either5::D(_))))
+}
+
+// 按选项 id 所在的 CURLOPTTYPE 区间推断类型
+fn infer_type(id: c_int, is_list: bool) -> CurlOptType {
+  match id / CURLOPTTYPE_OBJECTPOINT * CURLOPTTYPE_OBJECTPOINT {
+    CURLOPTTYPE_OBJECTPOINT if is_list => CurlOptType::Slist,
+    CURLOPTTYPE_OBJECTPOINT => CurlOptType::String,
+    CURLOPTTYPE_FUNCTIONPOINT => CurlOptType::Function,
+    CURLOPTTYPE_OFF_T => CurlOptType::OffT,
+    CURLOPTTYPE_BLOB => CurlOptType::Blob,
+    _ => CurlOptType::Long,
+  }
+}
+
+/// 通过 curl_easy_option_by_id 查询选项类型
+pub fn known_type(lib: &'static CurlFunctions, id: c_int) -> Option<CurlOptType> {
+  let by_id = lib.easy_option_by_id.get().ok()?;
+  let option = unsafe { by_id(id) } as *const CurlEasyOption;
+  if option.is_null() {
+    return None;
+  }
+  from_easytype(unsafe { (*option).type_ })
+}
+
+pub fn from_easytype(value: c_int) -> Option<CurlOptType> {
+  Some(match value {
+    0 => CurlOptType::Long,
+    1 => CurlOptType::Values,
+    2 => CurlOptType::OffT,
+    3 => CurlOptType::Object,
+    4 => CurlOptType::String,
+    5 => CurlOptType::Slist,
+    6 => CurlOptType::CbPtr,
+    7 => CurlOptType::Blob,
+    8 => CurlOptType::Function,
+    _ => return None,
+  })
+}
+
+/// 参数与选项类型不符时的错误
+pub fn mismatch(id: c_int, opt_type: CurlOptType, value: &CurlOptValue) -> Error {
+  let expected = match opt_type {
+    CurlOptType::Long | CurlOptType::Values => "a number or boolean",
+    CurlOptType::OffT => "a number",
+    CurlOptType::String => "a string",
+    CurlOptType::Slist => "an array of strings",
+    CurlOptType::Blob => "a Buffer or string",
+    CurlOptType::Object | CurlOptType::CbPtr | CurlOptType::Function => {
+      return Error::new(
+        Status::InvalidArg,
+        format!("Option {} ({:?}) is managed internally and cannot be set directly", id, opt_type),
+      );
+    }
+  };
+  let actual = match value {
+    Either5::A(_) => "string",
+    Either5::B(_) => "number",
+    Either5::C(_) => "boolean",
+    Either5::D(_) => "array",
+    Either5::E(_) => "Buffer",
+  };
+  Error::new(
+    Status::InvalidArg,
+    format!("Option {} ({:?}) expects {}, got {}", id, opt_type, expected, actual),
+  )
+}
+
+// curl_easyoption.flags：该名称是其他选项的别名（通常是旧名称）
+const CURLOT_FLAG_ALIAS: c_uint = 1 << 0;
+
+/// 已加载的 libcurl 支持的选项
+#[napi(object)]
+pub struct CurlOptionInfo {
+  /// 不带 CURLOPT_ 前缀的名称，如 TIMEOUT_MS
+  pub name: String,
+  pub id: i32,
+  #[napi(js_name = "type")]
+  pub opt_type: Option<CurlOptType>,
+  pub flags: u32,
+  /// 是否为旧名称的别名
+  pub alias: bool,
+  /// 是否已收录在 CurlOpt 枚举中
+  pub known: bool,
+}
+
+/// 列出已加载的 libcurl 支持的全部选项
+#[napi]
+pub fn get_curl_options() -> Result<Vec<CurlOptionInfo>> {
+  list_options(napi_load_library()?)
+}
+
+pub(crate) fn list_options(lib: &'static CurlFunctions) -> Result<Vec<CurlOptionInfo>> {
+  let mut options = Vec::new();
+  let next = lib.easy_option_next.get()?;
+  let mut option: *const CurlEasyOption = std::ptr::null();
+  loop {
+    option = unsafe { next(option as *const c_void) as *const CurlEasyOption };
+    if option.is_null() {
+      break;
+    }
+    options.push(unsafe { option_info(&*option) });
+  }
+  Ok(options)
+}
+
+/// 按名称（可带 CURLOPT_ 前缀，不区分大小写）或 id 查询选项
+#[napi]
+pub fn get_curl_option(option: Either<String, i32>) -> Result<Option<CurlOptionInfo>> {
+  let lib = napi_load_library()?;
+  let option = match option {
+    Either::A(name) => find_by_name(lib, &name)?,
+    Either::B(id) => unsafe { (lib.easy_option_by_id.get()?)(id) as *const CurlEasyOption },
+  };
+  if option.is_null() {
+    return Ok(None);
+  }
+  Ok(Some(unsafe { option_info(&*option) }))
+}
+
+/// curl_easy_option_by_name，名称未收录时返回空指针
+pub fn find_by_name(lib: &'static CurlFunctions, name: &str) -> Result<*const CurlEasyOption> {
+  let name = strip_prefix(name);
+  let name = CString::new(name)
+    .map_err(|_| Error::new(Status::InvalidArg, "Option name contains a nul byte"))?;
+  let by_name = lib.easy_option_by_name.get()?;
+  Ok(unsafe { by_name(name.as_ptr()) as *const CurlEasyOption })
+}
+
+fn strip_prefix(name: &str) -> &str {
+  match name.get(..8) {
+    Some(prefix) if prefix.eq_ignore_ascii_case("CURLOPT_") => &name[8..],
+    _ => name,
+  }
+}
+
+unsafe fn option_info(option: &CurlEasyOption) -> CurlOptionInfo {
+  let name = if option.name.is_null() {
+    String::new()
+  } else {
+    CStr::from_ptr(option.name).to_string_lossy().into_owned()
+  };
+  CurlOptionInfo {
+    name,
+    id: option.id,
+    opt_type: from_easytype(option.type_),
+    flags: option.flags,
+    alias: option.flags & CURLOT_FLAG_ALIAS != 0,
+    known: CurlOpt::try_from(option.id).is_ok(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn infers_type_from_option_range() {
+    assert_eq!(
+      infer_type(CurlOpt::Verbose as c_int, false),
+      CurlOptType::Long
+    );
+    assert_eq!(
+      infer_type(CurlOpt::WriteFunction as c_int, false),
+      CurlOptType::Function
+    );
+    assert_eq!(
+      infer_type(CurlOpt::MaxFileSizeLarge as c_int, false),
+      CurlOptType::OffT
+    );
+    assert_eq!(
+      infer_type(CurlOpt::SslCertBlob as c_int, false),
+      CurlOptType::Blob
+    );
+    // 数组只影响指针区间
+    assert_eq!(
+      infer_type(CurlOpt::Verbose as c_int, true),
+      CurlOptType::Long
+    );
+  }
+
+  #[test]
+  fn pointer_range_depends_on_value() {
+    let id = CurlOpt::HttpHeader as c_int;
+    assert_eq!(infer_type(id, true), CurlOptType::Slist);
+    assert_eq!(infer_type(id, false), CurlOptType::String);
+  }
+
+  #[test]
+  fn recognizes_curl_opt_ids() {
+    assert!(matches!(CurlOpt::try_from(10002), Ok(CurlOpt::Url)));
+    assert!(matches!(CurlOpt::try_from(41), Ok(CurlOpt::Verbose)));
+    assert!(matches!(
+      CurlOpt::try_from(1021),
+      Ok(CurlOpt::TlsUseFirefoxTls13Ciphers)
+    ));
+    // 紧邻已知选项的空缺 id
+    assert_eq!(CurlOpt::try_from(1022).err(), Some(1022));
+    assert_eq!(CurlOpt::try_from(10008).err(), Some(10008));
+    assert_eq!(CurlOpt::try_from(99999).err(), Some(99999));
+  }
+
+  #[test]
+  fn maps_curl_easytype() {
+    assert_eq!(from_easytype(0), Some(CurlOptType::Long));
+    assert_eq!(from_easytype(1), Some(CurlOptType::Values));
+    assert_eq!(from_easytype(5), Some(CurlOptType::Slist));
+    assert_eq!(from_easytype(8), Some(CurlOptType::Function));
+    assert_eq!(from_easytype(9), None);
+    assert_eq!(from_easytype(-1), None);
+  }
+}
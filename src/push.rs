@@ -0,0 +1,358 @@
+//! HTTP/2 服务器推送：CURLMOPT_PUSHFUNCTION 回调与推送传输的管理
+
+use std::{
+  collections::HashMap,
+  ffi::{c_char, c_int, c_void, CStr},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+  },
+};
+
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+
+use crate::{
+  constants::CurlOpt,
+  curl::Curl,
+  loader::{CurlFunctions, CurlHandle, CurlMultiHandle},
+  log_info,
+  response::ResponseHeader,
+  utils::get_ptr_address,
+};
+
+// CURLMOPT_PUSHFUNCTION 的返回值
+const CURL_PUSH_OK: c_int = 0;
+const CURL_PUSH_DENY: c_int = 1;
+
+/// PUSH_PROMISE 携带的伪头部与头部
+#[napi(object)]
+#[derive(Clone)]
+pub struct CurlPushPromise {
+  /// 推送传输的 id，与交付的 Curl.id() 一致
+  pub id: String,
+  /// 发起原请求的 Curl id
+  pub parent_id: String,
+  pub method: Option<String>,
+  pub scheme: Option<String>,
+  pub authority: Option<String>,
+  pub path: Option<String>,
+  pub headers: Vec<ResponseHeader>,
+}
+
+/// 已接受的推送传输完成后交付给 JS
+#[napi(object, object_from_js = false)]
+pub struct CurlPushed {
+  pub promise: CurlPushPromise,
+  /// 传输结果（CurlError），0 表示成功
+  pub result: i32,
+  pub curl: Curl,
+}
+
+/// 返回 true 接受推送，其余情况拒绝
+pub type PushCallback = ThreadsafeFunction<CurlPushPromise, Option<bool>>;
+pub type PushedCallback = ThreadsafeFunction<CurlPushed>;
+
+struct PushHandlers {
+  handler: Arc<PushCallback>,
+  pushed: Arc<PushedCallback>,
+}
+
+struct PushedTransfer {
+  // 传输期间 libcurl 持有其中缓冲区的地址，完成后才能移交给 JS
+  curl: Box<Curl>,
+  promise: CurlPushPromise,
+  accepted: Option<bool>,
+  result: Option<c_int>,
+}
+
+// JS 处理函数是异步调用的：推送先被 libcurl 接受，JS 拒绝后再取消
+pub(crate) struct PushRegistry {
+  lib: &'static CurlFunctions,
+  multi: CurlMultiHandle,
+  // 与 addTransfer 共用的 CURLOPT_PRIVATE id
+  next_id: Arc<AtomicUsize>,
+  handlers: Mutex<Option<PushHandlers>>,
+  pushes: Mutex<HashMap<usize, PushedTransfer>>,
+  // 已被 JS 拒绝、等待在驱动线程中取消的推送
+  denied: Mutex<Vec<usize>>,
+  // 唤醒驱动线程，未设置时使用 multi_wakeup
+  waker: Mutex<Option<Box<dyn Fn() + Send>>>,
+}
+
+unsafe impl Send for PushRegistry {}
+unsafe impl Sync for PushRegistry {}
+
+impl PushRegistry {
+  pub(crate) fn new(
+    lib: &'static CurlFunctions,
+    multi: CurlMultiHandle,
+    next_id: Arc<AtomicUsize>,
+  ) -> Self {
+    PushRegistry {
+      lib,
+      multi,
+      next_id,
+      handlers: Mutex::new(None),
+      pushes: Mutex::new(HashMap::new()),
+      denied: Mutex::new(Vec::new()),
+      waker: Mutex::new(None),
+    }
+  }
+
+  /// 驱动不经过 multi_poll 时（如 libuv 驱动），由其提供唤醒方式
+  pub(crate) fn set_waker(&self, waker: Option<Box<dyn Fn() + Send>>) {
+    if let Ok(mut current) = self.waker.lock() {
+      *current = waker;
+    }
+  }
+
+  fn wakeup(&self) {
+    match self.waker.lock().as_deref() {
+      Ok(Some(waker)) => waker(),
+      _ => unsafe {
+        (self.lib.multi_wakeup)(self.multi);
+      },
+    }
+  }
+
+  pub(crate) fn set_handlers(&self, handlers: Option<(Arc<PushCallback>, Arc<PushedCallback>)>) {
+    if let Ok(mut current) = self.handlers.lock() {
+      *current = handlers.map(|(handler, pushed)| PushHandlers { handler, pushed });
+    }
+  }
+
+  pub(crate) fn has_handlers(&self) -> bool {
+    self.handlers.lock().is_ok_and(|handlers| handlers.is_some())
+  }
+
+  pub(crate) fn has_pushes(&self) -> bool {
+    self.pushes.lock().is_ok_and(|pushes| !pushes.is_empty())
+  }
+
+  fn on_push(
+    self: &Arc<Self>,
+    parent: CurlHandle,
+    easy: CurlHandle,
+    num_headers: usize,
+    headers: *mut c_void,
+  ) -> c_int {
+    let handler = match self.handlers.lock() {
+      Ok(handlers) => match handlers.as_ref() {
+        Some(handlers) => handlers.handler.clone(),
+        None => return CURL_PUSH_DENY,
+      },
+      Err(_) => return CURL_PUSH_DENY,
+    };
+    // 返回 DENY 时 libcurl 自行释放 easy，因此失败时需先交还句柄
+    let mut curl = match Curl::from_handle(self.lib, easy) {
+      Ok(curl) => Box::new(curl),
+      Err(_) => return CURL_PUSH_DENY,
+    };
+    let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    curl.init();
+    // 推送句柄复制自原请求，需要覆盖其 CURLOPT_PRIVATE，并清除指向原请求追踪记录的 CURLOPT_DEBUGDATA
+    if curl.set_opt(CurlOpt::Private, id as *const c_void).is_err()
+      || curl.clear_trace_options().is_err()
+    {
+      curl.release_handle();
+      return CURL_PUSH_DENY;
+    }
+
+    let promise = unsafe { read_promise(self.lib, parent, easy, num_headers, headers) };
+    log_info!(
+      "CurlMulti",
+      "Server push {} {:?} from {}",
+      id,
+      promise.path,
+      promise.parent_id
+    );
+    if let Ok(mut pushes) = self.pushes.lock() {
+      pushes.insert(
+        id,
+        PushedTransfer {
+          curl,
+          promise: promise.clone(),
+          accepted: None,
+          result: None,
+        },
+      );
+    }
+
+    let registry = self.clone();
+    handler.call_with_return_value(
+      Ok(promise),
+      ThreadsafeFunctionCallMode::NonBlocking,
+      move |ret, _| {
+        registry.decide(id, matches!(ret, Ok(Some(true))));
+        Ok(())
+      },
+    );
+    CURL_PUSH_OK
+  }
+
+  /// 在 JS 线程中处理接受或拒绝
+  fn decide(&self, id: usize, accept: bool) {
+    let entry = {
+      let Ok(mut pushes) = self.pushes.lock() else {
+        return;
+      };
+      let Some(entry) = pushes.get_mut(&id) else {
+        return;
+      };
+      entry.accepted = Some(accept);
+      if entry.result.is_none() {
+        if !accept {
+          // multi handle 只能在驱动它的线程中操作，唤醒后由其取消
+          if let Ok(mut denied) = self.denied.lock() {
+            denied.push(id);
+          }
+          self.wakeup();
+        }
+        return;
+      }
+      pushes.remove(&id)
+    };
+    if let Some(entry) = entry.filter(|_| accept) {
+      self.deliver(entry);
+    }
+  }
+
+  /// 处理推送传输的 CURLMSG_DONE，不是推送时返回 false
+  pub(crate) fn complete(&self, id: usize, code: c_int) -> bool {
+    let entry = {
+      let Ok(mut pushes) = self.pushes.lock() else {
+        return false;
+      };
+      let Some(entry) = pushes.get_mut(&id) else {
+        return false;
+      };
+      unsafe { (self.lib.multi_remove_handle)(self.multi, entry.curl.get_handle()) };
+      entry.result = Some(code);
+      match entry.accepted {
+        Some(_) => pushes.remove(&id),
+        None => None,
+      }
+    };
+    if let Some(entry) = entry.filter(|entry| entry.accepted == Some(true)) {
+      self.deliver(entry);
+    }
+    true
+  }
+
+  fn deliver(&self, entry: PushedTransfer) {
+    let pushed = match self.handlers.lock() {
+      Ok(handlers) => handlers.as_ref().map(|handlers| handlers.pushed.clone()),
+      Err(_) => None,
+    };
+    if let Some(pushed) = pushed {
+      pushed.call(
+        Ok(CurlPushed {
+          promise: entry.promise,
+          result: entry.result.unwrap_or_default(),
+          curl: *entry.curl,
+        }),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+    }
+  }
+
+  /// 在驱动 multi 的线程中取消被拒绝的推送
+  pub(crate) fn cancel_denied(&self) {
+    let denied = match self.denied.lock() {
+      Ok(mut denied) => std::mem::take(&mut *denied),
+      Err(_) => return,
+    };
+    if denied.is_empty() {
+      return;
+    }
+    let Ok(mut pushes) = self.pushes.lock() else {
+      return;
+    };
+    for id in denied {
+      if let Some(entry) = pushes.remove(&id) {
+        log_info!("CurlMulti", "Cancel server push {}", id);
+        unsafe { (self.lib.multi_remove_handle)(self.multi, entry.curl.get_handle()) };
+      }
+    }
+  }
+
+  /// 关闭 multi 前移除并释放所有推送
+  pub(crate) fn clear(&self) {
+    self.set_waker(None);
+    if let Ok(mut denied) = self.denied.lock() {
+      denied.clear();
+    }
+    let pushes = match self.pushes.lock() {
+      Ok(mut pushes) => std::mem::take(&mut *pushes),
+      Err(_) => return,
+    };
+    for entry in pushes.into_values() {
+      if entry.result.is_none() {
+        unsafe { (self.lib.multi_remove_handle)(self.multi, entry.curl.get_handle()) };
+      }
+    }
+  }
+}
+
+pub(crate) extern "C" fn push_callback(
+  parent: CurlHandle,
+  easy: CurlHandle,
+  num_headers: usize,
+  headers: *mut c_void,
+  userptr: *mut c_void,
+) -> c_int {
+  if userptr.is_null() {
+    return CURL_PUSH_DENY;
+  }
+  // 从原始指针重新构造 Arc，但不释放所有权
+  let registry = unsafe { Arc::from_raw(userptr as *const PushRegistry) };
+  let result = registry.on_push(parent, easy, num_headers, headers);
+  std::mem::forget(registry);
+  result
+}
+
+unsafe fn read_promise(
+  lib: &'static CurlFunctions,
+  parent: CurlHandle,
+  easy: CurlHandle,
+  num_headers: usize,
+  headers: *mut c_void,
+) -> CurlPushPromise {
+  let by_name = |name: &[u8]| {
+    let value = (lib.pushheader_byname)(headers, name.as_ptr() as *const c_char);
+    to_string(value)
+  };
+  let mut list = Vec::new();
+  for i in 0..num_headers {
+    // 格式为 "name:value"，伪头部单独读取
+    let Some(header) = to_string((lib.pushheader_bynum)(headers, i)) else {
+      continue;
+    };
+    if header.starts_with(':') {
+      continue;
+    }
+    if let Some((name, value)) = header.split_once(':') {
+      list.push(ResponseHeader {
+        name: name.to_string(),
+        value: value.trim_start().to_string(),
+      });
+    }
+  }
+  CurlPushPromise {
+    id: get_ptr_address(easy),
+    parent_id: get_ptr_address(parent),
+    method: by_name(b":method\0"),
+    scheme: by_name(b":scheme\0"),
+    authority: by_name(b":authority\0"),
+    path: by_name(b":path\0"),
+    headers: list,
+  }
+}
+
+unsafe fn to_string(value: *mut c_char) -> Option<String> {
+  if value.is_null() {
+    return None;
+  }
+  Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+}
@@ -0,0 +1,158 @@
+//! 解析 header_buffer 中的原始响应头，按跳（重定向、1xx、CONNECT）拆分
+
+use napi_derive::napi;
+
+// 单个响应头，保留原始大小写
+#[napi(object)]
+#[derive(Clone)]
+pub struct ResponseHeader {
+  pub name: String,
+  pub value: String,
+}
+
+// 一跳响应
+#[napi(object)]
+pub struct ResponseHop {
+  pub status: i32,
+  /// HTTP 版本，如 "1.1"、"2"、"3"
+  pub http_version: String,
+  /// 原因短语，HTTP/2 及以上通常为空
+  pub reason: String,
+  /// 按接收顺序排列的响应头，同名头部保留多条
+  pub headers: Vec<ResponseHeader>,
+}
+
+/// 解析状态行，如 "HTTP/1.1 200 OK"
+fn parse_status_line(line: &str) -> Option<ResponseHop> {
+  let rest = line.strip_prefix("HTTP/")?;
+  let mut parts = rest.splitn(3, ' ');
+  let http_version = parts.next()?.to_string();
+  let status = parts.next()?.trim().parse::<i32>().ok()?;
+  let reason = parts.next().unwrap_or("").trim().to_string();
+  Some(ResponseHop {
+    status,
+    http_version,
+    reason,
+    headers: Vec::new(),
+  })
+}
+
+/// 将原始响应头数据解析为按跳排列的响应列表
+pub fn parse_response_hops(raw: &[u8]) -> Vec<ResponseHop> {
+  let text = String::from_utf8_lossy(raw);
+  let mut hops: Vec<ResponseHop> = Vec::new();
+  // 当前是否处于某一跳的头部块内
+  let mut in_block = false;
+
+  for line in text.split('\n') {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if line.is_empty() {
+      in_block = false;
+      continue;
+    }
+    if !in_block {
+      if let Some(hop) = parse_status_line(line) {
+        hops.push(hop);
+        in_block = true;
+        continue;
+      }
+    }
+    let Some(hop) = hops.last_mut() else {
+      continue;
+    };
+    // 以空白开头的行是上一个头部的折行
+    if line.starts_with([' ', '\t']) {
+      if let Some(header) = hop.headers.last_mut() {
+        header.value.push(' ');
+        header.value.push_str(line.trim());
+      }
+      continue;
+    }
+    // 头部块之外的行（如 trailer）归入最后一跳
+    if let Some((name, value)) = line.split_once(':') {
+      hop.headers.push(ResponseHeader {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+      });
+    }
+  }
+
+  hops
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn header(hop: &ResponseHop, name: &str) -> Option<String> {
+    hop
+      .headers
+      .iter()
+      .find(|header| header.name.eq_ignore_ascii_case(name))
+      .map(|header| header.value.clone())
+  }
+
+  #[test]
+  fn parses_status_line() {
+    let hops = parse_response_hops(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+    assert_eq!(hops.len(), 1);
+    assert_eq!(hops[0].status, 404);
+    assert_eq!(hops[0].http_version, "1.1");
+    assert_eq!(hops[0].reason, "Not Found");
+    assert_eq!(header(&hops[0], "content-length").as_deref(), Some("0"));
+  }
+
+  #[test]
+  fn http2_status_line_has_no_reason() {
+    let hops = parse_response_hops(b"HTTP/2 200 \r\ncontent-type: text/html\r\n\r\n");
+    assert_eq!(hops[0].status, 200);
+    assert_eq!(hops[0].http_version, "2");
+    assert_eq!(hops[0].reason, "");
+  }
+
+  #[test]
+  fn splits_redirect_and_informational_hops() {
+    let raw = b"HTTP/1.1 100 Continue\r\n\r\n\
+HTTP/1.1 302 Found\r\nLocation: /next\r\n\r\n\
+HTTP/1.1 200 OK\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n\r\n";
+    let hops = parse_response_hops(raw);
+    let statuses: Vec<i32> = hops.iter().map(|hop| hop.status).collect();
+    assert_eq!(statuses, [100, 302, 200]);
+    assert_eq!(header(&hops[1], "location").as_deref(), Some("/next"));
+    // 同名头部保留多条
+    assert_eq!(hops[2].headers.len(), 2);
+  }
+
+  #[test]
+  fn proxy_connect_is_its_own_hop() {
+    let raw = b"HTTP/1.1 200 Connection established\r\n\r\n\
+HTTP/2 204 \r\nserver: test\r\n\r\n";
+    let hops = parse_response_hops(raw);
+    assert_eq!(hops.len(), 2);
+    assert_eq!(hops[0].reason, "Connection established");
+    assert!(hops[0].headers.is_empty());
+    assert_eq!(hops[1].status, 204);
+    assert_eq!(header(&hops[1], "server").as_deref(), Some("test"));
+  }
+
+  #[test]
+  fn joins_folded_headers() {
+    let raw = b"HTTP/1.1 200 OK\r\nX-Long: first\r\n\tsecond\r\n  third\r\n\r\n";
+    let hops = parse_response_hops(raw);
+    assert_eq!(header(&hops[0], "x-long").as_deref(), Some("first second third"));
+  }
+
+  #[test]
+  fn status_like_header_value_stays_in_block() {
+    let raw = b"HTTP/1.1 200 OK\r\nX-Note: HTTP/1.1 500 inside\r\n\r\n";
+    let hops = parse_response_hops(raw);
+    assert_eq!(hops.len(), 1);
+    assert_eq!(header(&hops[0], "x-note").as_deref(), Some("HTTP/1.1 500 inside"));
+  }
+
+  #[test]
+  fn ignores_garbage_before_first_status_line() {
+    assert!(parse_response_hops(b"garbage: value\r\n\r\n").is_empty());
+    assert!(parse_response_hops(b"").is_empty());
+  }
+}
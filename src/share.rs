@@ -0,0 +1,163 @@
+//! 共享句柄：在多个 Curl 之间共享 cookie、DNS、TLS 会话与连接缓存
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use std::os::raw::{c_int, c_void};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::api::share_strerror;
+use crate::constants::{CurlShOpt, CurlShareLock};
+use crate::library::{resolve_library, CurlLibrary};
+use crate::loader::{CurlFunctions, CurlHandle, CurlShare as RawShare};
+use crate::log_info;
+
+// curl_lock_data 的取值个数（CURL_LOCK_DATA_LAST）
+const LOCK_DATA_COUNT: usize = 8;
+
+// 跨 lock / unlock 两次回调持有的锁，MutexGuard 无法跨回调保存，用条件变量实现
+struct DataLock {
+  locked: Mutex<bool>,
+  cond: Condvar,
+}
+
+impl DataLock {
+  fn new() -> Self {
+    DataLock {
+      locked: Mutex::new(false),
+      cond: Condvar::new(),
+    }
+  }
+
+  fn lock(&self) {
+    let mut locked = self.locked.lock().unwrap_or_else(|e| e.into_inner());
+    while *locked {
+      locked = self.cond.wait(locked).unwrap_or_else(|e| e.into_inner());
+    }
+    *locked = true;
+  }
+
+  fn unlock(&self) {
+    let mut locked = self.locked.lock().unwrap_or_else(|e| e.into_inner());
+    *locked = false;
+    self.cond.notify_one();
+  }
+}
+
+// 共享句柄及其锁，由 CurlShare 与使用它的 Curl 共同持有，最后一个持有者释放
+pub struct ShareData {
+  handle: RawShare,
+  lib: &'static CurlFunctions,
+  // 每种 curl_lock_data 一把锁
+  locks: [DataLock; LOCK_DATA_COUNT],
+}
+
+unsafe impl Send for ShareData {}
+unsafe impl Sync for ShareData {}
+
+impl ShareData {
+  pub fn handle(&self) -> RawShare {
+    self.handle
+  }
+
+  pub fn lib(&self) -> &'static CurlFunctions {
+    self.lib
+  }
+
+  fn data_lock(&self, data: c_int) -> Option<&DataLock> {
+    usize::try_from(data).ok().and_then(|i| self.locks.get(i))
+  }
+}
+
+impl Drop for ShareData {
+  fn drop(&mut self) {
+    log_info!("CurlShare", "share_cleanup");
+    unsafe {
+      (self.lib.share_cleanup)(self.handle);
+    }
+  }
+}
+
+// libcurl 在工作线程中访问共享数据前调用
+extern "C" fn share_lock(_handle: CurlHandle, data: c_int, _access: c_int, userptr: *mut c_void) {
+  if userptr.is_null() {
+    return;
+  }
+  let share = unsafe { &*(userptr as *const ShareData) };
+  if let Some(lock) = share.data_lock(data) {
+    lock.lock();
+  }
+}
+
+extern "C" fn share_unlock(_handle: CurlHandle, data: c_int, userptr: *mut c_void) {
+  if userptr.is_null() {
+    return;
+  }
+  let share = unsafe { &*(userptr as *const ShareData) };
+  if let Some(lock) = share.data_lock(data) {
+    lock.unlock();
+  }
+}
+
+#[napi]
+pub struct CurlShare {
+  data: Arc<ShareData>,
+}
+
+#[napi]
+impl CurlShare {
+  /// library 默认为全局库
+  #[napi(constructor)]
+  pub fn new(library: Option<&CurlLibrary>) -> Result<Self> {
+    let lib = resolve_library(library)?;
+    let handle = unsafe { (lib.share_init)() };
+    if handle.is_null() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Failed to initialize share handle",
+      ));
+    }
+    let share = CurlShare {
+      data: Arc::new(ShareData {
+        handle,
+        lib,
+        locks: std::array::from_fn(|_| DataLock::new()),
+      }),
+    };
+    share.set_opt(CurlShOpt::LockFunc, share_lock as *const c_void)?;
+    share.set_opt(CurlShOpt::UnlockFunc, share_unlock as *const c_void)?;
+    share.set_opt(
+      CurlShOpt::UserData,
+      Arc::as_ptr(&share.data) as *const c_void,
+    )?;
+    Ok(share)
+  }
+
+  /// 开始共享某类数据
+  #[napi]
+  pub fn share(&self, data: CurlShareLock) -> Result<()> {
+    log_info!("CurlShare", "share {:?}", data);
+    self.set_opt(CurlShOpt::Share, data as usize as *const c_void)
+  }
+
+  /// 停止共享某类数据
+  #[napi]
+  pub fn unshare(&self, data: CurlShareLock) -> Result<()> {
+    log_info!("CurlShare", "unshare {:?}", data);
+    self.set_opt(CurlShOpt::Unshare, data as usize as *const c_void)
+  }
+
+  pub fn data(&self) -> Arc<ShareData> {
+    self.data.clone()
+  }
+
+  fn set_opt(&self, option: CurlShOpt, value: *const c_void) -> Result<()> {
+    let code = unsafe { (self.data.lib.share_setopt)(self.data.handle, option as c_int, value) };
+    if code != 0 {
+      return Err(Error::new(
+        Status::GenericFailure,
+        format!("failed with code: {} message:{}", code, share_strerror(self.data.lib, code)),
+      ));
+    }
+    Ok(())
+  }
+}
@@ -1,7 +1,10 @@
 use napi_derive::napi;
 
+// curl_socket_t
 #[cfg(unix)]
-use std::os::unix::io::RawFd;
+pub type CurlSocket = std::os::raw::c_int;
+#[cfg(windows)]
+pub type CurlSocket = usize;
 
 #[napi]
 pub fn socket_is_readable(sockfd: i32) -> bool {
@@ -13,6 +16,46 @@ pub fn socket_is_writable(sockfd: i32) -> bool {
   check_writable(sockfd)
 }
 
+/// 等待 socket 可读或可写，超时返回 false
+pub fn wait_socket(sockfd: i32, writable: bool, timeout_ms: i32) -> bool {
+  wait_ready(sockfd, writable, timeout_ms)
+}
+
+#[cfg(unix)]
+fn wait_ready(sockfd: i32, writable: bool, timeout_ms: i32) -> bool {
+  let mut pfd = libc::pollfd {
+    fd: sockfd,
+    events: if writable { libc::POLLOUT } else { libc::POLLIN },
+    revents: 0,
+  };
+  unsafe { libc::poll(&mut pfd, 1, timeout_ms) > 0 }
+}
+
+#[cfg(windows)]
+fn wait_ready(sockfd: i32, writable: bool, timeout_ms: i32) -> bool {
+  use std::mem;
+  use std::ptr;
+  use winapi::um::winsock2::{fd_set, select, timeval, SOCKET};
+
+  unsafe {
+    let mut fds: fd_set = mem::zeroed();
+    fds.fd_count = 1;
+    fds.fd_array[0] = sockfd as SOCKET;
+
+    let mut timeout = timeval {
+      tv_sec: timeout_ms / 1000,
+      tv_usec: (timeout_ms % 1000) * 1000,
+    };
+
+    let (read_fds, write_fds) = if writable {
+      (ptr::null_mut(), &mut fds as *mut fd_set)
+    } else {
+      (&mut fds as *mut fd_set, ptr::null_mut())
+    };
+    select(0, read_fds, write_fds, ptr::null_mut(), &mut timeout) > 0
+  }
+}
+
 #[cfg(unix)]
 fn check_readable(sockfd: i32) -> bool {
   use std::mem;
@@ -0,0 +1,150 @@
+//! 传输统计：一次性读取耗时、大小、速度、连接等信息
+
+use napi_derive::napi;
+
+use crate::{
+  constants::CurlInfo,
+  info::{info_number, info_string},
+  loader::{CurlFunctions, CurlHandle},
+};
+
+/// 传输统计快照，libcurl 不支持或尚未产生的信息为空
+///
+/// 时间均为微秒，且是从传输开始到该阶段结束的时间点，而非各阶段耗时
+#[napi(object)]
+pub struct CurlStats {
+  /// 在连接池队列中等待的时间（libcurl 8.6+）
+  pub queue_time: Option<i64>,
+  pub name_lookup_time: Option<i64>,
+  pub connect_time: Option<i64>,
+  /// TLS 握手完成，非 HTTPS 时为 0
+  pub app_connect_time: Option<i64>,
+  pub pre_transfer_time: Option<i64>,
+  /// 请求体发送完成（libcurl 8.10+）
+  pub post_transfer_time: Option<i64>,
+  /// 收到首字节
+  pub start_transfer_time: Option<i64>,
+  pub total_time: Option<i64>,
+  /// 所有重定向耗时之和
+  pub redirect_time: Option<i64>,
+
+  /// 下载的正文字节数（解压前）
+  pub size_download: Option<i64>,
+  pub size_upload: Option<i64>,
+  /// 收到的所有响应头字节数
+  pub header_size: Option<i64>,
+  /// 发出的所有请求字节数
+  pub request_size: Option<i64>,
+  /// Content-Length，未知时为空
+  pub content_length_download: Option<i64>,
+  pub content_length_upload: Option<i64>,
+  /// 平均下载速度（字节/秒）
+  pub speed_download: Option<i64>,
+  pub speed_upload: Option<i64>,
+
+  pub response_code: Option<i64>,
+  pub redirect_count: Option<i64>,
+  /// 本次传输新建的连接数
+  pub num_connects: Option<i64>,
+  pub primary_ip: Option<String>,
+  pub primary_port: Option<i64>,
+  pub local_ip: Option<String>,
+  pub local_port: Option<i64>,
+  /// 实际使用的 HTTP 版本，如 "1.1"、"2"、"3"
+  pub http_version: Option<String>,
+  /// 协议，如 "https"
+  pub scheme: Option<String>,
+  pub effective_url: Option<String>,
+  /// 传输 id（libcurl 8.2+）
+  pub xfer_id: Option<i64>,
+  /// 连接 id（libcurl 8.2+）
+  pub conn_id: Option<i64>,
+  /// 是否经过代理（libcurl 8.7+）
+  pub used_proxy: Option<bool>,
+}
+
+/// 读取传输统计，需在句柄所在的线程中调用
+pub(crate) unsafe fn read_stats(lib: &'static CurlFunctions, handle: CurlHandle) -> CurlStats {
+  let number = |info| info_number(lib, handle, info).map(|value| value as i64);
+  // 负数表示未知
+  let known = |info| number(info).filter(|value| *value >= 0);
+  let string = |info| info_string(lib, handle, info).filter(|value| !value.is_empty());
+  // 优先读取微秒精度的 *_T，旧版 libcurl 退回以秒为单位的 double
+  let time = |info, fallback: Option<CurlInfo>| {
+    number(info).or_else(|| {
+      fallback
+        .and_then(|fallback| info_number(lib, handle, fallback))
+        .map(|seconds| (seconds * 1_000_000.0).round() as i64)
+    })
+  };
+
+  CurlStats {
+    queue_time: time(CurlInfo::QueueTimeT, None),
+    name_lookup_time: time(CurlInfo::NameLookupTimeT, Some(CurlInfo::NameLookupTime)),
+    connect_time: time(CurlInfo::ConnectTimeT, Some(CurlInfo::ConnectTime)),
+    app_connect_time: time(CurlInfo::AppConnectTimeT, Some(CurlInfo::AppConnectTime)),
+    pre_transfer_time: time(CurlInfo::PreTransferTimeT, Some(CurlInfo::PreTransferTime)),
+    post_transfer_time: time(CurlInfo::PostTransferTimeT, None),
+    start_transfer_time: time(
+      CurlInfo::StartTransferTimeT,
+      Some(CurlInfo::StartTransferTime),
+    ),
+    total_time: time(CurlInfo::TotalTimeT, Some(CurlInfo::TotalTime)),
+    redirect_time: time(CurlInfo::RedirectTimeT, Some(CurlInfo::RedirectTime)),
+
+    size_download: number(CurlInfo::SizeDownloadT),
+    size_upload: number(CurlInfo::SizeUploadT),
+    header_size: number(CurlInfo::HeaderSize),
+    request_size: number(CurlInfo::RequestSize),
+    content_length_download: known(CurlInfo::ContentLengthDownloadT),
+    content_length_upload: known(CurlInfo::ContentLengthUploadT),
+    speed_download: number(CurlInfo::SpeedDownloadT),
+    speed_upload: number(CurlInfo::SpeedUploadT),
+
+    response_code: number(CurlInfo::ResponseCode),
+    redirect_count: number(CurlInfo::RedirectCount),
+    num_connects: number(CurlInfo::NumConnects),
+    primary_ip: string(CurlInfo::PrimaryIp),
+    primary_port: number(CurlInfo::PrimaryPort).filter(|port| *port > 0),
+    local_ip: string(CurlInfo::LocalIp),
+    local_port: number(CurlInfo::LocalPort).filter(|port| *port > 0),
+    http_version: number(CurlInfo::HttpVersion).and_then(http_version_name),
+    scheme: string(CurlInfo::Scheme).map(|scheme| scheme.to_lowercase()),
+    effective_url: string(CurlInfo::EffectiveUrl),
+    xfer_id: known(CurlInfo::XferId),
+    conn_id: known(CurlInfo::ConnId),
+    used_proxy: number(CurlInfo::UsedProxy).map(|used| used != 0),
+  }
+}
+
+// CURL_HTTP_VERSION_* 转为版本号，0 表示没有 HTTP 响应
+fn http_version_name(version: i64) -> Option<String> {
+  match version {
+    1 => Some("1.0".to_string()),
+    2 => Some("1.1".to_string()),
+    3 => Some("2".to_string()),
+    30 => Some("3".to_string()),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn names_http_versions() {
+    assert_eq!(http_version_name(1).as_deref(), Some("1.0"));
+    assert_eq!(http_version_name(2).as_deref(), Some("1.1"));
+    assert_eq!(http_version_name(3).as_deref(), Some("2"));
+    assert_eq!(http_version_name(30).as_deref(), Some("3"));
+  }
+
+  #[test]
+  fn unknown_http_version_is_none() {
+    // 0 表示没有 HTTP 响应，4 为 CURL_HTTP_VERSION_2TLS 只用于请求
+    assert_eq!(http_version_name(0), None);
+    assert_eq!(http_version_name(4), None);
+    assert_eq!(http_version_name(-1), None);
+  }
+}
@@ -0,0 +1,491 @@
+//! 流式传输：响应体分块投递给 JS、请求体从 JS 分块读取，以及传输进度上报
+
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::api::easy_strerror;
+use crate::loader::{CurlFunctions, CurlHandle};
+
+// 写回调返回该值时 libcurl 暂停接收，恢复后会重新投递同一数据块
+pub const CURL_WRITEFUNC_PAUSE: usize = 0x10000001;
+// 读回调返回值：中止传输 / 暂停发送
+pub const CURL_READFUNC_ABORT: usize = 0x10000000;
+pub const CURL_READFUNC_PAUSE: usize = 0x10000001;
+// 定位回调返回值：成功 / 无法定位
+pub const CURL_SEEKFUNC_OK: c_int = 0;
+pub const CURL_SEEKFUNC_CANTSEEK: c_int = 2;
+// curl_easy_pause 的 CURLPAUSE_CONT
+pub const CURLPAUSE_CONT: c_int = 0;
+// 流式传输默认的缓冲字节上限
+pub const DEFAULT_HIGH_WATER_MARK: usize = 1024 * 1024;
+
+// 传输进度
+#[napi(object)]
+pub struct ProgressData {
+  pub dltotal: i64,
+  pub dlnow: i64,
+  pub ultotal: i64,
+  pub ulnow: i64,
+}
+
+// 进度回调，JS 返回 true 时中止传输
+pub type ProgressCallback = ThreadsafeFunction<ProgressData, Option<bool>>;
+
+// 流式响应体回调：依次收到数据块，传输结束时收到 null，失败时收到错误
+pub type BodyCallback = ThreadsafeFunction<Option<Buffer>>;
+
+// 唤醒驱动传输的线程处理恢复请求
+pub type Waker = Arc<dyn Fn() + Send + Sync>;
+
+struct ProgressReporter {
+  callback: Arc<ProgressCallback>,
+  interval: Duration,
+  last: Option<(Instant, [i64; 4])>,
+}
+
+// 传输控制：暂停恢复与进度上报，作为 xferinfo 回调的上下文
+// curl_easy_pause 不能跨线程调用：在工作线程中执行时只记录恢复请求，由 xferinfo 回调代为恢复
+pub struct TransferControl {
+  handle: CurlHandle,
+  lib: &'static CurlFunctions,
+  // perform 是否正在工作线程中执行
+  pub performing: AtomicBool,
+  // 是否已加入 CurlMulti，期间 libcurl 仍引用 Curl 持有的数据
+  attached: AtomicBool,
+  resume: AtomicBool,
+  // 由 CurlMulti 的 reactor 线程驱动时唤醒该线程，恢复请求在其中处理
+  waker: Mutex<Option<Waker>>,
+  progress: Mutex<Option<ProgressReporter>>,
+  // 流式响应体回调，传输结束时投递结束信号
+  body: Mutex<Option<Arc<BodyCallback>>>,
+  // 本次传输是否已投递结束信号
+  body_ended: AtomicBool,
+  // JS 进度回调请求中止
+  abort: Arc<AtomicBool>,
+}
+
+unsafe impl Send for TransferControl {}
+unsafe impl Sync for TransferControl {}
+
+impl TransferControl {
+  pub fn new(handle: CurlHandle, lib: &'static CurlFunctions) -> Self {
+    TransferControl {
+      handle,
+      lib,
+      performing: AtomicBool::new(false),
+      attached: AtomicBool::new(false),
+      resume: AtomicBool::new(false),
+      waker: Mutex::new(None),
+      progress: Mutex::new(None),
+      body: Mutex::new(None),
+      body_ended: AtomicBool::new(false),
+      abort: Arc::new(AtomicBool::new(false)),
+    }
+  }
+
+  /// 设置进度回调，interval 为两次上报的最小间隔
+  pub fn set_progress(&self, callback: Option<Arc<ProgressCallback>>, interval: Duration) {
+    if let Ok(mut progress) = self.progress.lock() {
+      *progress = callback.map(|callback| ProgressReporter {
+        callback,
+        interval,
+        last: None,
+      });
+    }
+  }
+
+  pub fn set_body(&self, callback: Option<Arc<BodyCallback>>) {
+    if let Ok(mut body) = self.body.lock() {
+      *body = callback;
+    }
+  }
+
+  /// 传输结束时投递流式响应体的结束信号：code 为 0 时为 null，否则为错误
+  ///
+  /// 在传输结束的线程中、Promise 结算之前调用，信号排在所有数据块之后；每次传输只投递一次
+  pub fn end_body(&self, code: c_int) {
+    if code == 0 {
+      self.finish_body(Ok(()));
+    } else {
+      self.finish_body(Err(format!(
+        "failed with code: {} message:{}",
+        code,
+        easy_strerror(self.lib, code)
+      )));
+    }
+  }
+
+  /// 传输未完成即被移除或拒绝时以 reason 结束流式响应体
+  pub fn fail_body(&self, reason: &str) {
+    self.finish_body(Err(reason.to_string()));
+  }
+
+  fn finish_body(&self, result: std::result::Result<(), String>) {
+    let Some(callback) = self.body.lock().ok().and_then(|body| body.clone()) else {
+      return;
+    };
+    if self.body_ended.swap(true, Ordering::SeqCst) {
+      return;
+    }
+    let value = match result {
+      Ok(()) => Ok(None),
+      Err(reason) => Err(napi::Error::from_reason(reason)),
+    };
+    callback.call(value, ThreadsafeFunctionCallMode::NonBlocking);
+  }
+
+  pub fn has_progress(&self) -> bool {
+    self.progress.lock().map(|p| p.is_some()).unwrap_or(false)
+  }
+
+  fn report(&self, values: [i64; 4]) {
+    let Ok(mut progress) = self.progress.lock() else {
+      return;
+    };
+    let Some(reporter) = progress.as_mut() else {
+      return;
+    };
+    let now = Instant::now();
+    if let Some((at, last)) = reporter.last {
+      if last == values || now.duration_since(at) < reporter.interval {
+        return;
+      }
+    }
+    reporter.last = Some((now, values));
+    let abort = self.abort.clone();
+    let [dltotal, dlnow, ultotal, ulnow] = values;
+    reporter.callback.call_with_return_value(
+      Ok(ProgressData {
+        dltotal,
+        dlnow,
+        ultotal,
+        ulnow,
+      }),
+      ThreadsafeFunctionCallMode::NonBlocking,
+      move |ret, _| {
+        if let Ok(Some(true)) = ret {
+          abort.store(true, Ordering::SeqCst);
+        }
+        Ok(())
+      },
+    );
+  }
+
+  pub fn is_performing(&self) -> bool {
+    self.performing.load(Ordering::SeqCst)
+  }
+
+  pub fn set_attached(&self, attached: bool) {
+    self.attached.store(attached, Ordering::SeqCst);
+  }
+
+  pub fn is_attached(&self) -> bool {
+    self.attached.load(Ordering::SeqCst)
+  }
+
+  /// 由 reactor 线程驱动时传入其唤醒函数，传 None 表示交回 JS 线程
+  pub fn set_reactor(&self, waker: Option<Waker>) {
+    self.performing.store(waker.is_some(), Ordering::SeqCst);
+    if let Ok(mut current) = self.waker.lock() {
+      *current = waker;
+    }
+  }
+
+  /// 请求恢复被暂停的传输
+  pub fn request_resume(&self) {
+    if self.is_performing() {
+      self.resume.store(true, Ordering::SeqCst);
+      // 暂停的传输在 reactor 线程中可能不再触发 xferinfo，需唤醒后由其恢复
+      if let Some(waker) = self.waker.lock().ok().and_then(|waker| waker.clone()) {
+        waker();
+      }
+    } else {
+      self.unpause();
+    }
+  }
+
+  /// 在 libcurl 回调或 reactor 线程中处理挂起的恢复请求
+  pub fn poll_resume(&self) {
+    if self.resume.swap(false, Ordering::SeqCst) {
+      self.unpause();
+    }
+  }
+
+  pub fn reset(&self) {
+    self.resume.store(false, Ordering::SeqCst);
+    self.body_ended.store(false, Ordering::SeqCst);
+    self.abort.store(false, Ordering::SeqCst);
+    if let Ok(mut progress) = self.progress.lock() {
+      if let Some(reporter) = progress.as_mut() {
+        reporter.last = None;
+      }
+    }
+  }
+
+  fn unpause(&self) {
+    unsafe {
+      (self.lib.easy_pause)(self.handle, CURLPAUSE_CONT);
+    }
+  }
+}
+
+// 流式响应体状态，数据块通过 TSFN 投递给 JS，未消费字节超过上限时暂停传输
+pub struct BodyStream {
+  control: Arc<TransferControl>,
+  callback: Arc<BodyCallback>,
+  high_water_mark: usize,
+  // 已投递但 JS 尚未确认消费的字节数
+  pending: AtomicUsize,
+  paused: AtomicBool,
+}
+
+impl BodyStream {
+  pub fn new(
+    control: Arc<TransferControl>,
+    callback: Arc<BodyCallback>,
+    high_water_mark: usize,
+  ) -> Self {
+    BodyStream {
+      control,
+      callback,
+      high_water_mark,
+      pending: AtomicUsize::new(0),
+      paused: AtomicBool::new(false),
+    }
+  }
+
+  pub fn reset(&self) {
+    self.pending.store(0, Ordering::SeqCst);
+    self.paused.store(false, Ordering::SeqCst);
+  }
+
+  /// JS 确认消费 bytes 字节
+  pub fn consume(&self, bytes: usize) {
+    let _ = self
+      .pending
+      .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+        Some(v.saturating_sub(bytes))
+      });
+    if self.pending.load(Ordering::SeqCst) < self.high_water_mark
+      && self.paused.swap(false, Ordering::SeqCst)
+    {
+      self.control.request_resume();
+    }
+  }
+}
+
+// 流式响应体写入回调
+pub extern "C" fn stream_data(
+  ptr: *mut c_char,
+  size: usize,
+  nmemb: usize,
+  userp: *mut c_void,
+) -> usize {
+  let real_size = size * nmemb;
+  if ptr.is_null() || userp.is_null() || real_size == 0 {
+    return real_size;
+  }
+  let stream = unsafe { &*(userp as *const BodyStream) };
+  if stream.pending.load(Ordering::SeqCst) >= stream.high_water_mark {
+    // 先标记暂停再复查：consume 若在两者之间把 pending 降到上限以下，会因看不到 paused 而不恢复
+    stream.paused.store(true, Ordering::SeqCst);
+    if stream.pending.load(Ordering::SeqCst) >= stream.high_water_mark {
+      return CURL_WRITEFUNC_PAUSE;
+    }
+    // consume 已经抢先清除 paused 时，多出的恢复请求对未暂停的传输无影响
+    stream.paused.store(false, Ordering::SeqCst);
+  }
+  let data = unsafe { std::slice::from_raw_parts(ptr as *const u8, real_size) };
+  stream.pending.fetch_add(real_size, Ordering::SeqCst);
+  stream.callback.call(
+    Ok(Some(Buffer::from(data.to_vec()))),
+    ThreadsafeFunctionCallMode::NonBlocking,
+  );
+  real_size
+}
+
+// 请求体数据来源
+enum UploadSource {
+  // JS 通过 write_upload 写入的数据队列
+  Queue(Mutex<UploadQueue>),
+  File(Mutex<File>),
+}
+
+struct UploadQueue {
+  data: VecDeque<u8>,
+  ended: bool,
+  paused: bool,
+}
+
+// 流式请求体状态
+pub struct UploadStream {
+  control: Arc<TransferControl>,
+  source: UploadSource,
+  // 已知的请求体大小，未知时使用 chunked 编码
+  pub size: Option<i64>,
+  high_water_mark: usize,
+  on_drain: Option<Arc<ThreadsafeFunction<()>>>,
+}
+
+impl UploadStream {
+  pub fn from_queue(
+    control: Arc<TransferControl>,
+    size: Option<i64>,
+    high_water_mark: usize,
+    on_drain: Option<Arc<ThreadsafeFunction<()>>>,
+  ) -> Self {
+    UploadStream {
+      control,
+      source: UploadSource::Queue(Mutex::new(UploadQueue {
+        data: VecDeque::new(),
+        ended: false,
+        paused: false,
+      })),
+      size,
+      high_water_mark,
+      on_drain,
+    }
+  }
+
+  pub fn from_file(control: Arc<TransferControl>, file: File, size: i64) -> Self {
+    UploadStream {
+      control,
+      source: UploadSource::File(Mutex::new(file)),
+      size: Some(size),
+      high_water_mark: DEFAULT_HIGH_WATER_MARK,
+      on_drain: None,
+    }
+  }
+
+  /// 文件来源在每次传输前回到开头
+  pub fn rewind(&self) {
+    let _ = self.seek(0);
+  }
+
+  /// 定位到 offset 处，JS 写入的数据读出后即丢弃，无法定位
+  pub fn seek(&self, offset: u64) -> bool {
+    match &self.source {
+      UploadSource::File(file) => file
+        .lock()
+        .is_ok_and(|mut file| file.seek(SeekFrom::Start(offset)).is_ok()),
+      UploadSource::Queue(_) => false,
+    }
+  }
+
+  /// 写入一块请求体数据，返回 false 表示缓冲已满，应等待 drain 回调
+  pub fn write(&self, chunk: &[u8]) -> Result<bool, &'static str> {
+    let UploadSource::Queue(queue) = &self.source else {
+      return Err("Upload stream is backed by a file");
+    };
+    let mut queue = queue.lock().map_err(|_| "Upload queue is poisoned")?;
+    if queue.ended {
+      return Err("Upload stream has already ended");
+    }
+    queue.data.extend(chunk);
+    let below = queue.data.len() < self.high_water_mark;
+    if std::mem::take(&mut queue.paused) {
+      self.control.request_resume();
+    }
+    Ok(below)
+  }
+
+  /// 结束请求体
+  pub fn end(&self) -> Result<(), &'static str> {
+    let UploadSource::Queue(queue) = &self.source else {
+      return Err("Upload stream is backed by a file");
+    };
+    let mut queue = queue.lock().map_err(|_| "Upload queue is poisoned")?;
+    queue.ended = true;
+    if std::mem::take(&mut queue.paused) {
+      self.control.request_resume();
+    }
+    Ok(())
+  }
+}
+
+// 流式请求体读取回调
+pub extern "C" fn stream_read(
+  buffer: *mut c_char,
+  size: usize,
+  nitems: usize,
+  userp: *mut c_void,
+) -> usize {
+  let capacity = size * nitems;
+  if buffer.is_null() || userp.is_null() || capacity == 0 {
+    return 0;
+  }
+  let stream = unsafe { &*(userp as *const UploadStream) };
+  let out = unsafe { std::slice::from_raw_parts_mut(buffer as *mut u8, capacity) };
+  match &stream.source {
+    UploadSource::File(file) => match file.lock() {
+      Ok(mut file) => file.read(out).unwrap_or(CURL_READFUNC_ABORT),
+      Err(_) => CURL_READFUNC_ABORT,
+    },
+    UploadSource::Queue(queue) => {
+      let Ok(mut queue) = queue.lock() else {
+        return CURL_READFUNC_ABORT;
+      };
+      if queue.data.is_empty() {
+        if queue.ended {
+          return 0;
+        }
+        queue.paused = true;
+        return CURL_READFUNC_PAUSE;
+      }
+      let was_full = queue.data.len() >= stream.high_water_mark;
+      let n = capacity.min(queue.data.len());
+      for (dst, src) in out.iter_mut().zip(queue.data.drain(..n)) {
+        *dst = src;
+      }
+      if was_full && queue.data.len() < stream.high_water_mark {
+        if let Some(on_drain) = &stream.on_drain {
+          on_drain.call(Ok(()), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+      }
+      n
+    }
+  }
+}
+
+// 流式请求体定位回调：重定向、认证重试需要重发请求体时由 libcurl 调用
+pub extern "C" fn stream_seek(userp: *mut c_void, offset: i64, origin: c_int) -> c_int {
+  // libcurl 只使用 SEEK_SET
+  if userp.is_null() || origin != 0 || offset < 0 {
+    return CURL_SEEKFUNC_CANTSEEK;
+  }
+  let stream = unsafe { &*(userp as *const UploadStream) };
+  if stream.seek(offset as u64) {
+    CURL_SEEKFUNC_OK
+  } else {
+    CURL_SEEKFUNC_CANTSEEK
+  }
+}
+
+// xferinfo 回调：上报进度；工作线程中 curl_easy_pause 只能在 libcurl 自己的回调里调用，借此恢复传输
+// 返回非 0 时 libcurl 以 CURLE_ABORTED_BY_CALLBACK 中止传输
+pub extern "C" fn xferinfo_callback(
+  clientp: *mut c_void,
+  dltotal: i64,
+  dlnow: i64,
+  ultotal: i64,
+  ulnow: i64,
+) -> c_int {
+  if clientp.is_null() {
+    return 0;
+  }
+  let control = unsafe { &*(clientp as *const TransferControl) };
+  if control.abort.load(Ordering::SeqCst) {
+    return 1;
+  }
+  control.report([dltotal, dlnow, ultotal, ulnow]);
+  control.poll_resume();
+  0
+}
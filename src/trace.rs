@@ -0,0 +1,290 @@
+//! 线路追踪：通过 CURLOPT_DEBUGFUNCTION 记录每个 easy handle 收发的原始字节
+
+use std::ffi::{c_char, c_int, c_void};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+
+use crate::{
+  constants::{CurlInfo, CurlInfoType},
+  info::info_off_t,
+  loader::{CurlFunctions, CurlHandle},
+  log_warn,
+  utils::get_ptr_address,
+};
+
+/// 一条追踪记录
+#[napi(object)]
+pub struct TraceEntry {
+  #[napi(js_name = "type")]
+  pub kind: CurlInfoType,
+  /// 原始字节，TEXT 与 HEADER_* 可直接按文本解码
+  pub data: Buffer,
+  /// 毫秒时间戳
+  pub timestamp: f64,
+  /// 连接 id（CURLINFO_CONN_ID），libcurl 不支持或尚未建立连接时为空
+  pub conn_id: Option<i64>,
+  /// 传输 id（CURLINFO_XFER_ID）
+  pub xfer_id: Option<i64>,
+}
+
+#[napi(object)]
+pub struct TraceOptions {
+  /// 只记录这些类型，默认全部
+  pub types: Option<Vec<CurlInfoType>>,
+  /// 收集到内存时 data 的总字节上限，超出后丢弃后续记录，默认不限制
+  pub max_bytes: Option<u32>,
+}
+
+pub type TraceCallback = ThreadsafeFunction<TraceEntry>;
+
+struct TraceRecord {
+  kind: CurlInfoType,
+  data: Vec<u8>,
+  timestamp: f64,
+  conn_id: Option<i64>,
+  xfer_id: Option<i64>,
+}
+
+impl TraceRecord {
+  fn into_entry(self) -> TraceEntry {
+    TraceEntry {
+      kind: self.kind,
+      data: self.data.into(),
+      timestamp: self.timestamp,
+      conn_id: self.conn_id,
+      xfer_id: self.xfer_id,
+    }
+  }
+}
+
+#[derive(Default)]
+struct TraceBuffer {
+  records: Vec<TraceRecord>,
+  bytes: usize,
+  // 是否因 max_bytes 丢弃过记录
+  truncated: bool,
+}
+
+struct TraceOutput {
+  // 设置回调时交给 JS，否则收集到内存
+  callback: Option<Arc<TraceCallback>>,
+  // 按 CurlInfoType 取值的位掩码
+  types: u32,
+  max_bytes: Option<usize>,
+}
+
+/// HAR 记录所需的最近一次请求：实际发出的请求头与请求体
+#[derive(Default)]
+pub(crate) struct RequestCapture {
+  pub(crate) headers: Vec<u8>,
+  pub(crate) body: Vec<u8>,
+  /// 请求体的实际字节数，body 只保留前 max_body 字节
+  pub(crate) body_size: usize,
+  max_body: usize,
+  // 收到响应后再出现的请求头属于新的请求（重定向、重试）
+  responded: bool,
+}
+
+impl RequestCapture {
+  fn new(max_body: usize) -> Self {
+    RequestCapture {
+      max_body,
+      ..Default::default()
+    }
+  }
+
+  fn record(&mut self, kind: CurlInfoType, data: &[u8]) {
+    match kind {
+      CurlInfoType::HeaderOut => {
+        if self.responded {
+          *self = RequestCapture::new(self.max_body);
+        }
+        self.headers.extend_from_slice(data);
+      }
+      CurlInfoType::DataOut => {
+        let keep = self.max_body.saturating_sub(self.body.len()).min(data.len());
+        self.body.extend_from_slice(&data[..keep]);
+        self.body_size += data.len();
+      }
+      CurlInfoType::HeaderIn => self.responded = true,
+      _ => {}
+    }
+  }
+}
+
+// 作为 CURLOPT_DEBUGDATA 传给 libcurl，由 Curl 持有直到句柄释放或 reset
+pub(crate) struct TraceState {
+  lib: &'static CurlFunctions,
+  // enableTrace 开启的追踪输出
+  output: Mutex<Option<TraceOutput>>,
+  buffer: Mutex<TraceBuffer>,
+  // 记录 HAR 时开启
+  capture: Mutex<Option<RequestCapture>>,
+}
+
+impl TraceState {
+  pub(crate) fn new(lib: &'static CurlFunctions) -> Self {
+    TraceState {
+      lib,
+      output: Mutex::new(None),
+      buffer: Mutex::new(TraceBuffer::default()),
+      capture: Mutex::new(None),
+    }
+  }
+
+  /// 开启或关闭追踪输出，同时丢弃未取出的记录
+  pub(crate) fn set_output(
+    &self,
+    enable: bool,
+    callback: Option<Arc<TraceCallback>>,
+    options: Option<TraceOptions>,
+  ) {
+    let output = enable.then(|| {
+      let (types, max_bytes) = match options {
+        Some(options) => (
+          options
+            .types
+            .map(|types| types.iter().fold(0, |mask, kind| mask | 1 << *kind as u32))
+            .unwrap_or(u32::MAX),
+          options.max_bytes.map(|max| max as usize),
+        ),
+        None => (u32::MAX, None),
+      };
+      TraceOutput {
+        callback,
+        types,
+        max_bytes,
+      }
+    });
+    if let Ok(mut current) = self.output.lock() {
+      *current = output;
+    }
+    if let Ok(mut buffer) = self.buffer.lock() {
+      *buffer = TraceBuffer::default();
+    }
+  }
+
+  /// 开启（max_body 为请求体保留的字节数）或关闭请求采集
+  pub(crate) fn set_capture(&self, max_body: Option<usize>) {
+    if let Ok(mut capture) = self.capture.lock() {
+      match (max_body, capture.as_mut()) {
+        (Some(max_body), Some(capture)) => capture.max_body = max_body,
+        (max_body, _) => *capture = max_body.map(RequestCapture::new),
+      }
+    }
+  }
+
+  /// 是否需要 libcurl 调用 CURLOPT_DEBUGFUNCTION
+  pub(crate) fn is_active(&self) -> bool {
+    self.output.lock().is_ok_and(|output| output.is_some())
+      || self.capture.lock().is_ok_and(|capture| capture.is_some())
+  }
+
+  /// 取出已收集的记录
+  pub(crate) fn take(&self) -> Vec<TraceEntry> {
+    match self.buffer.lock() {
+      Ok(mut buffer) => std::mem::take(&mut *buffer)
+        .records
+        .into_iter()
+        .map(TraceRecord::into_entry)
+        .collect(),
+      Err(_) => Vec::new(),
+    }
+  }
+
+  /// 取出最近一次请求，未开启采集时为空
+  pub(crate) fn take_request(&self) -> Option<RequestCapture> {
+    let mut capture = self.capture.lock().ok()?;
+    let max_body = capture.as_ref()?.max_body;
+    capture.replace(RequestCapture::new(max_body))
+  }
+
+  fn record(&self, handle: CurlHandle, kind: CurlInfoType, data: &[u8]) {
+    if let Ok(mut capture) = self.capture.lock() {
+      if let Some(capture) = capture.as_mut() {
+        capture.record(kind, data);
+      }
+    }
+
+    let Ok(output) = self.output.lock() else {
+      return;
+    };
+    let Some(output) = output.as_ref() else {
+      return;
+    };
+    if output.types & (1 << kind as u32) == 0 {
+      return;
+    }
+    let record = TraceRecord {
+      kind,
+      data: data.to_vec(),
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+        .unwrap_or_default(),
+      conn_id: self.get_id(handle, CurlInfo::ConnId),
+      xfer_id: self.get_id(handle, CurlInfo::XferId),
+    };
+    if let Some(callback) = &output.callback {
+      callback.call(
+        Ok(record.into_entry()),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+      return;
+    }
+    if let Ok(mut buffer) = self.buffer.lock() {
+      if output
+        .max_bytes
+        .is_some_and(|max| buffer.bytes + record.data.len() > max)
+      {
+        if !buffer.truncated {
+          buffer.truncated = true;
+          log_warn!("Curl", handle = get_ptr_address(handle); "Trace exceeds maxBytes, dropping further records");
+        }
+        return;
+      }
+      buffer.bytes += record.data.len();
+      buffer.records.push(record);
+    }
+  }
+
+  // 回调中可以调用 curl_easy_getinfo；旧版 libcurl 不支持时返回错误
+  fn get_id(&self, handle: CurlHandle, info: CurlInfo) -> Option<i64> {
+    unsafe { info_off_t(self.lib, handle, info) }.filter(|id| *id >= 0)
+  }
+}
+
+pub(crate) extern "C" fn debug_callback(
+  handle: CurlHandle,
+  kind: c_int,
+  data: *mut c_char,
+  size: usize,
+  userptr: *mut c_void,
+) -> c_int {
+  if userptr.is_null() {
+    return 0;
+  }
+  let kind = match kind {
+    0 => CurlInfoType::Text,
+    1 => CurlInfoType::HeaderIn,
+    2 => CurlInfoType::HeaderOut,
+    3 => CurlInfoType::DataIn,
+    4 => CurlInfoType::DataOut,
+    5 => CurlInfoType::SslDataIn,
+    6 => CurlInfoType::SslDataOut,
+    _ => return 0,
+  };
+  let data = if data.is_null() || size == 0 {
+    &[][..]
+  } else {
+    unsafe { std::slice::from_raw_parts(data as *const u8, size) }
+  };
+  // Curl 持有 TraceState 直到句柄释放或 reset，期间指针有效
+  let state = unsafe { &*(userptr as *const TraceState) };
+  state.record(handle, kind, data);
+  0
+}
@@ -0,0 +1,160 @@
+//! 基于 curl_url 的 URL 解析与构建，规则与 libcurl 发起请求时一致
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int, c_uint};
+use std::sync::Arc;
+
+use crate::api::url_strerror;
+use crate::constants::{CurlUError, CurlUFlag, CurlUPart};
+use crate::library::{resolve_library, CurlLibrary};
+use crate::loader::{CurlFunctions, CurlUrl as RawUrl};
+use crate::log_info;
+
+// CURLU 句柄，由 CurlUrl 与使用它的 Curl 共同持有，最后一个持有者释放
+pub struct UrlData {
+  handle: RawUrl,
+  lib: &'static CurlFunctions,
+}
+
+unsafe impl Send for UrlData {}
+unsafe impl Sync for UrlData {}
+
+impl UrlData {
+  pub fn handle(&self) -> RawUrl {
+    self.handle
+  }
+
+  pub fn lib(&self) -> &'static CurlFunctions {
+    self.lib
+  }
+}
+
+impl Drop for UrlData {
+  fn drop(&mut self) {
+    log_info!("CurlUrl", "url_cleanup");
+    unsafe {
+      (self.lib.url_cleanup)(self.handle);
+    }
+  }
+}
+
+#[napi]
+pub struct CurlUrl {
+  data: Arc<UrlData>,
+}
+
+#[napi]
+impl CurlUrl {
+  /// 创建 URL，可选地解析 url；flags 为 CurlUFlag 的按位组合，library 默认为全局库
+  #[napi(constructor)]
+  pub fn new(url: Option<String>, flags: Option<u32>, library: Option<&CurlLibrary>) -> Result<Self> {
+    let lib = resolve_library(library)?;
+    let handle = unsafe { (lib.url)() };
+    let curl_url = Self::from_raw(lib, handle)?;
+    if let Some(url) = url {
+      curl_url.set(CurlUPart::Url, Some(url), flags)?;
+    }
+    Ok(curl_url)
+  }
+
+  /// 设置某一部分，value 为 null 时清除该部分
+  ///
+  /// 设置 Url 时相对地址会基于当前 URL 解析；Query 配合 CurlUFlag.AppendQuery 追加查询参数
+  #[napi]
+  pub fn set(&self, part: CurlUPart, value: Option<String>, flags: Option<u32>) -> Result<()> {
+    let value = value
+      .map(CString::new)
+      .transpose()
+      .map_err(|_| Error::new(Status::InvalidArg, "Invalid url string"))?;
+    let code = unsafe {
+      (self.data.lib.url_set)(
+        self.data.handle,
+        part as c_int,
+        value.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+        flags.unwrap_or(0) as c_uint,
+      )
+    };
+    check(self.data.lib, code)
+  }
+
+  /// 获取某一部分，不存在时返回 null
+  #[napi]
+  pub fn get(&self, part: CurlUPart, flags: Option<u32>) -> Result<Option<String>> {
+    let mut ptr: *mut c_char = std::ptr::null_mut();
+    let code = unsafe {
+      (self.data.lib.url_get)(
+        self.data.handle,
+        part as c_int,
+        &mut ptr,
+        flags.unwrap_or(0) as c_uint,
+      )
+    };
+    if (CurlUError::NoScheme as c_int..=CurlUError::NoZoneId as c_int).contains(&code) {
+      return Ok(None);
+    }
+    check(self.data.lib, code)?;
+    if ptr.is_null() {
+      return Ok(None);
+    }
+    let value = unsafe { std::ffi::CStr::from_ptr(ptr).to_string_lossy().to_string() };
+    unsafe {
+      (self.data.lib.free)(ptr as *mut _);
+    }
+    Ok(Some(value))
+  }
+
+  /// 追加一个查询参数，name=value 会按 libcurl 规则编码
+  #[napi]
+  pub fn append_query(&self, name: String, value: Option<String>) -> Result<()> {
+    let query = match value {
+      Some(value) => format!("{}={}", name, value),
+      None => name,
+    };
+    self.set(
+      CurlUPart::Query,
+      Some(query),
+      Some(CurlUFlag::AppendQuery as u32 | CurlUFlag::UrlEncode as u32),
+    )
+  }
+
+  /// 完整 URL
+  #[napi]
+  pub fn href(&self, flags: Option<u32>) -> Result<String> {
+    Ok(self.get(CurlUPart::Url, flags)?.unwrap_or_default())
+  }
+
+  /// 复制为独立的 CurlUrl
+  #[napi]
+  pub fn duplicate(&self) -> Result<CurlUrl> {
+    let handle = unsafe { (self.data.lib.url_dup)(self.data.handle) };
+    Self::from_raw(self.data.lib, handle)
+  }
+
+  pub fn data(&self) -> Arc<UrlData> {
+    self.data.clone()
+  }
+
+  fn from_raw(lib: &'static CurlFunctions, handle: RawUrl) -> Result<Self> {
+    if handle.is_null() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Failed to initialize url handle",
+      ));
+    }
+    Ok(CurlUrl {
+      data: Arc::new(UrlData { handle, lib }),
+    })
+  }
+}
+
+fn check(lib: &'static CurlFunctions, code: c_int) -> Result<()> {
+  if code != 0 {
+    return Err(Error::new(
+      Status::GenericFailure,
+      format!("failed with code: {} message:{}", code, url_strerror(lib, code)),
+    ));
+  }
+  Ok(())
+}
@@ -1,5 +1,5 @@
 use napi_derive::napi;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn get_ptr_address<T>(ptr: *const T) -> String {
   format!("0x{:x}", ptr as usize)
@@ -33,16 +33,19 @@ pub fn get_default_lib_path(base: Option<String>) -> String {
 
   let dir_name = get_default_dir_name();
 
-  let lib_name = match std::env::consts::OS {
-    "windows" => Path::new("bin").join("libcurl.dll"),
-    "macos" => Path::new("libcurl-impersonate.dylib").to_path_buf(),
-    "linux" => Path::new("libcurl-impersonate.so").to_path_buf(),
-    _ => Path::new("libcurl-impersonate.so").to_path_buf(),
-  };
-
   base_dir
     .join(dir_name)
-    .join(lib_name)
+    .join(default_lib_name())
     .to_string_lossy()
     .to_string()
 }
+
+/// 发布包中动态库相对于解压目录的路径
+pub fn default_lib_name() -> PathBuf {
+  match std::env::consts::OS {
+    "windows" => Path::new("bin").join("libcurl.dll"),
+    "macos" => Path::new("libcurl-impersonate.dylib").to_path_buf(),
+    "linux" => Path::new("libcurl-impersonate.so").to_path_buf(),
+    _ => Path::new("libcurl-impersonate.so").to_path_buf(),
+  }
+}
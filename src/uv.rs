@@ -0,0 +1,137 @@
+//! 从宿主 node 进程加载 libuv，供 CurlMulti 的内置事件循环驱动使用
+
+use libloading::{Library, Symbol};
+use napi::{Error, Result};
+use once_cell::sync::OnceCell;
+use std::alloc::Layout;
+use std::os::raw::{c_int, c_void};
+
+// uv_handle_type
+const UV_POLL: c_int = 8;
+const UV_TIMER: c_int = 13;
+
+// uv_poll_event
+pub const UV_READABLE: c_int = 1;
+pub const UV_WRITABLE: c_int = 2;
+
+// uv_os_sock_t
+#[cfg(unix)]
+pub type UvOsSock = c_int;
+#[cfg(windows)]
+pub type UvOsSock = usize;
+
+pub type UvLoop = *mut c_void;
+pub type UvHandle = *mut c_void;
+
+pub type UvPollCb = extern "C" fn(handle: UvHandle, status: c_int, events: c_int);
+pub type UvTimerCb = extern "C" fn(handle: UvHandle);
+pub type UvCloseCb = extern "C" fn(handle: UvHandle);
+
+type UvHandleSize = unsafe extern "C" fn(handle_type: c_int) -> usize;
+type UvPollInitSocket =
+  unsafe extern "C" fn(uv_loop: UvLoop, handle: UvHandle, socket: UvOsSock) -> c_int;
+type UvPollStart = unsafe extern "C" fn(handle: UvHandle, events: c_int, cb: UvPollCb) -> c_int;
+type UvPollStop = unsafe extern "C" fn(handle: UvHandle) -> c_int;
+type UvTimerInit = unsafe extern "C" fn(uv_loop: UvLoop, handle: UvHandle) -> c_int;
+type UvTimerStart =
+  unsafe extern "C" fn(handle: UvHandle, cb: UvTimerCb, timeout: u64, repeat: u64) -> c_int;
+type UvTimerStop = unsafe extern "C" fn(handle: UvHandle) -> c_int;
+type UvClose = unsafe extern "C" fn(handle: UvHandle, close_cb: UvCloseCb);
+type UvRef = unsafe extern "C" fn(handle: UvHandle);
+
+static UV_FUNCTIONS: OnceCell<UvFunctions> = OnceCell::new();
+
+pub struct UvFunctions {
+  pub poll_init_socket: Symbol<'static, UvPollInitSocket>,
+  pub poll_start: Symbol<'static, UvPollStart>,
+  pub poll_stop: Symbol<'static, UvPollStop>,
+  pub timer_init: Symbol<'static, UvTimerInit>,
+  pub timer_start: Symbol<'static, UvTimerStart>,
+  pub timer_stop: Symbol<'static, UvTimerStop>,
+  pub close: Symbol<'static, UvClose>,
+  pub ref_handle: Symbol<'static, UvRef>,
+  pub unref_handle: Symbol<'static, UvRef>,
+  poll_layout: Layout,
+  timer_layout: Layout,
+}
+
+unsafe impl Send for UvFunctions {}
+unsafe impl Sync for UvFunctions {}
+
+#[cfg(unix)]
+fn host_library() -> std::result::Result<Library, libloading::Error> {
+  Ok(libloading::os::unix::Library::this().into())
+}
+
+#[cfg(windows)]
+fn host_library() -> std::result::Result<Library, libloading::Error> {
+  Ok(libloading::os::windows::Library::this()?.into())
+}
+
+fn load() -> std::result::Result<UvFunctions, libloading::Error> {
+  let lib: &'static Library = Box::leak(Box::new(host_library()?));
+  let handle_size: Symbol<UvHandleSize> = unsafe { lib.get(b"uv_handle_size\0")? };
+  // 句柄内存由我们分配，大小以宿主 libuv 为准
+  let layout = |handle_type| {
+    let size = unsafe { handle_size(handle_type) };
+    Layout::from_size_align(size.max(1), 16).expect("invalid uv handle layout")
+  };
+  Ok(UvFunctions {
+    poll_init_socket: unsafe { lib.get(b"uv_poll_init_socket\0")? },
+    poll_start: unsafe { lib.get(b"uv_poll_start\0")? },
+    poll_stop: unsafe { lib.get(b"uv_poll_stop\0")? },
+    timer_init: unsafe { lib.get(b"uv_timer_init\0")? },
+    timer_start: unsafe { lib.get(b"uv_timer_start\0")? },
+    timer_stop: unsafe { lib.get(b"uv_timer_stop\0")? },
+    close: unsafe { lib.get(b"uv_close\0")? },
+    ref_handle: unsafe { lib.get(b"uv_ref\0")? },
+    unref_handle: unsafe { lib.get(b"uv_unref\0")? },
+    poll_layout: layout(UV_POLL),
+    timer_layout: layout(UV_TIMER),
+  })
+}
+
+pub fn napi_load_uv() -> Result<&'static UvFunctions> {
+  UV_FUNCTIONS
+    .get_or_try_init(load)
+    .map_err(|e| Error::from_reason(format!("Failed to load libuv from host: {}", e)))
+}
+
+impl UvFunctions {
+  /// 分配 uv_poll_t，data 字段写入 data
+  pub fn alloc_poll(&self, data: *mut c_void) -> UvHandle {
+    alloc_handle(self.poll_layout, data)
+  }
+
+  pub fn alloc_timer(&self, data: *mut c_void) -> UvHandle {
+    alloc_handle(self.timer_layout, data)
+  }
+
+  pub fn free_poll(&self, handle: UvHandle) {
+    unsafe { std::alloc::dealloc(handle as *mut u8, self.poll_layout) }
+  }
+
+  pub fn free_timer(&self, handle: UvHandle) {
+    unsafe { std::alloc::dealloc(handle as *mut u8, self.timer_layout) }
+  }
+}
+
+fn alloc_handle(layout: Layout, data: *mut c_void) -> UvHandle {
+  unsafe {
+    let handle = std::alloc::alloc_zeroed(layout) as UvHandle;
+    if handle.is_null() {
+      std::alloc::handle_alloc_error(layout);
+    }
+    set_data(handle, data);
+    handle
+  }
+}
+
+/// uv_handle_t 的第一个字段是 void* data
+pub fn get_data(handle: UvHandle) -> *mut c_void {
+  unsafe { *(handle as *mut *mut c_void) }
+}
+
+pub fn set_data(handle: UvHandle, data: *mut c_void) {
+  unsafe { *(handle as *mut *mut c_void) = data }
+}
@@ -0,0 +1,478 @@
+//! 基于 curl_ws_send / curl_ws_recv 的 WebSocket 客户端
+
+use napi::bindgen_prelude::{AsyncTask, Buffer, Either5, Reference};
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Either, Env, Error, Result};
+use napi_derive::napi;
+use std::os::raw::{c_int, c_uint, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::api::easy_strerror;
+use crate::constants::{CurlInfo, CurlOpt, CurlWsFlag};
+use crate::curl::{Curl, PerformTask};
+use crate::loader::{CurlFunctions, CurlHandle, CurlWsFrame};
+use crate::log_info;
+use crate::socket::{wait_socket, CurlSocket};
+
+// CURLE_AGAIN：非阻塞收发暂时无数据
+const CURLE_AGAIN: c_int = 81;
+// CURLE_GOT_NOTHING：对端已关闭连接
+const CURLE_GOT_NOTHING: c_int = 52;
+const WS_BUFFER_SIZE: usize = 64 * 1024;
+// 等待 socket 就绪的单次超时，期间会检查关闭状态
+const WS_POLL_MS: i32 = 100;
+// 关闭握手等待对端 CLOSE 帧的时间
+const WS_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 收到的一条完整消息（分片已重组）
+#[napi(object)]
+pub struct WsMessage {
+  pub data: Buffer,
+  /// CurlWsFlag 的按位组合，Text / Binary / Close / Ping / Pong
+  pub flags: u32,
+  /// CLOSE 帧的状态码
+  pub close_code: Option<u32>,
+  /// CLOSE 帧的原因
+  pub close_reason: Option<String>,
+}
+
+pub struct RawMessage {
+  data: Vec<u8>,
+  flags: u32,
+}
+
+impl RawMessage {
+  fn empty() -> Self {
+    RawMessage {
+      data: Vec::new(),
+      flags: 0,
+    }
+  }
+
+  fn into_message(self) -> WsMessage {
+    let is_close = self.flags & CurlWsFlag::Close as u32 != 0;
+    let (close_code, close_reason) = if is_close && self.data.len() >= 2 {
+      (
+        Some(u16::from_be_bytes([self.data[0], self.data[1]]) as u32),
+        Some(String::from_utf8_lossy(&self.data[2..]).to_string()),
+      )
+    } else {
+      (None, None)
+    };
+    WsMessage {
+      data: Buffer::from(self.data),
+      flags: self.flags,
+      close_code,
+      close_reason,
+    }
+  }
+}
+
+// 跨线程共享的连接状态，所有 ws_send / ws_recv 调用都在 io 锁内进行
+pub(crate) struct WsShared {
+  handle: CurlHandle,
+  lib: &'static CurlFunctions,
+  // 锁内的值表示 easy handle 是否已随 Curl 关闭而释放
+  io: Mutex<bool>,
+  close_sent: AtomicBool,
+  closed: AtomicBool,
+  // 是否已有 on_message 监听线程
+  listening: AtomicBool,
+  // 尚未收齐的分片数据消息，返回穿插的控制帧后下次接收继续重组
+  partial: Mutex<RawMessage>,
+}
+
+unsafe impl Send for WsShared {}
+unsafe impl Sync for WsShared {}
+
+impl WsShared {
+  /// Curl 释放 easy handle 前调用：等待进行中的收发结束，之后的收发都返回错误，监听线程随之退出
+  pub(crate) fn release(&self) {
+    self.closed.store(true, Ordering::SeqCst);
+    let mut released = self.io.lock().unwrap_or_else(|err| err.into_inner());
+    *released = true;
+  }
+
+  // 在 io 锁内使用 easy handle
+  fn with_handle<T>(&self, f: impl FnOnce(CurlHandle) -> T) -> Result<T> {
+    let released = self
+      .io
+      .lock()
+      .map_err(|_| Error::from_reason("WebSocket lock poisoned"))?;
+    if *released {
+      return Err(Error::from_reason("Curl instance is closed"));
+    }
+    Ok(f(self.handle))
+  }
+
+  fn socket(&self) -> Result<i32> {
+    let mut sockfd: CurlSocket = 0;
+    let code = self.with_handle(|handle| unsafe {
+      (self.lib.easy_getinfo)(
+        handle,
+        CurlInfo::ActiveSocket as c_int,
+        &mut sockfd as *mut _ as *mut c_void,
+      )
+    })?;
+    if code != 0 {
+      return Err(Error::from_reason(format!(
+        "failed with code: {} message:{}",
+        code,
+        easy_strerror(self.lib, code)
+      )));
+    }
+    Ok(sockfd as i32)
+  }
+
+  fn send_frame(&self, data: &[u8], flags: c_uint) -> Result<()> {
+    let mut offset = 0;
+    loop {
+      let mut sent = 0;
+      let ws_send = self.lib.ws_send.get()?;
+      let code = self.with_handle(|handle| unsafe {
+        ws_send(
+          handle,
+          data[offset..].as_ptr() as *const c_void,
+          data.len() - offset,
+          &mut sent,
+          0,
+          flags,
+        )
+      })?;
+      match code {
+        0 => {
+          offset += sent;
+          if offset >= data.len() {
+            return Ok(());
+          }
+        }
+        CURLE_AGAIN => {
+          wait_socket(self.socket()?, true, WS_POLL_MS);
+        }
+        _ => {
+          return Err(Error::from_reason(format!(
+            "ws_send failed with code: {} message:{}",
+            code,
+            easy_strerror(self.lib, code)
+          )))
+        }
+      }
+    }
+  }
+
+  fn send_close(&self, code: u16, reason: &[u8]) -> Result<()> {
+    if self.close_sent.swap(true, Ordering::SeqCst) {
+      return Ok(());
+    }
+    let mut payload = code.to_be_bytes().to_vec();
+    payload.extend_from_slice(reason);
+    self.send_frame(&payload, CurlWsFlag::Close as c_uint)
+  }
+
+  /// 接收一条完整消息，连接关闭后返回 None
+  fn recv_message(&self) -> Result<Option<RawMessage>> {
+    let mut buffer = vec![0u8; WS_BUFFER_SIZE];
+    // 数据帧与控制帧分别累积，控制帧可能穿插在分片之间
+    let mut partial = self
+      .partial
+      .lock()
+      .map_err(|_| Error::from_reason("WebSocket lock poisoned"))?;
+    let mut message = std::mem::replace(&mut *partial, RawMessage::empty());
+    let mut control = RawMessage::empty();
+    loop {
+      if self.closed.load(Ordering::SeqCst) {
+        return Ok(None);
+      }
+      let mut received = 0;
+      let mut meta: *const CurlWsFrame = std::ptr::null();
+      let ws_recv = self.lib.ws_recv.get()?;
+      let code = match self.with_handle(|handle| unsafe {
+        ws_recv(
+          handle,
+          buffer.as_mut_ptr() as *mut c_void,
+          buffer.len(),
+          &mut received,
+          &mut meta,
+        )
+      }) {
+        Ok(code) => code,
+        // Curl 已关闭，按连接关闭处理
+        Err(_) if self.closed.load(Ordering::SeqCst) => return Ok(None),
+        Err(err) => return Err(err),
+      };
+      match code {
+        0 => {}
+        CURLE_AGAIN => {
+          wait_socket(self.socket()?, false, WS_POLL_MS);
+          continue;
+        }
+        CURLE_GOT_NOTHING => {
+          self.closed.store(true, Ordering::SeqCst);
+          return Ok(None);
+        }
+        _ => {
+          return Err(Error::from_reason(format!(
+            "ws_recv failed with code: {} message:{}",
+            code,
+            easy_strerror(self.lib, code)
+          )))
+        }
+      }
+      if meta.is_null() {
+        continue;
+      }
+      let frame = unsafe { &*meta };
+      let flags = frame.flags as u32;
+      let is_control = flags
+        & (CurlWsFlag::Close as u32 | CurlWsFlag::Ping as u32 | CurlWsFlag::Pong as u32)
+        != 0;
+      let target = if is_control {
+        &mut control
+      } else {
+        &mut message
+      };
+      target.data.extend_from_slice(&buffer[..received]);
+      target.flags |= flags & !(CurlWsFlag::Cont as u32);
+      if frame.bytesleft > 0 {
+        continue;
+      }
+      if is_control {
+        let control = std::mem::replace(&mut control, RawMessage::empty());
+        if control.flags & CurlWsFlag::Close as u32 != 0 {
+          // 回应对端的关闭握手
+          let code = if control.data.len() >= 2 {
+            u16::from_be_bytes([control.data[0], control.data[1]])
+          } else {
+            1000
+          };
+          let _ = self.send_close(code, b"");
+          self.closed.store(true, Ordering::SeqCst);
+        }
+        // PING 的 PONG 由 libcurl 自动回复；已收到的分片留待下次继续重组
+        *partial = message;
+        return Ok(Some(control));
+      }
+      if flags & CurlWsFlag::Cont as u32 == 0 {
+        return Ok(Some(message));
+      }
+    }
+  }
+}
+
+pub struct WsSendTask {
+  shared: Arc<WsShared>,
+  data: Vec<u8>,
+  flags: c_uint,
+}
+
+impl napi::Task for WsSendTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.shared.send_frame(&self.data, self.flags)
+  }
+
+  fn resolve(&mut self, _env: napi::Env, _output: Self::Output) -> Result<Self::JsValue> {
+    Ok(())
+  }
+
+  fn reject(&mut self, _env: napi::Env, err: Error) -> Result<Self::JsValue> {
+    Err(err)
+  }
+}
+
+pub struct WsRecvTask {
+  shared: Arc<WsShared>,
+}
+
+impl napi::Task for WsRecvTask {
+  type Output = Option<RawMessage>;
+  type JsValue = Option<WsMessage>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.shared.recv_message()
+  }
+
+  fn resolve(&mut self, _env: napi::Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.map(RawMessage::into_message))
+  }
+
+  fn reject(&mut self, _env: napi::Env, err: Error) -> Result<Self::JsValue> {
+    Err(err)
+  }
+}
+
+pub struct WsCloseTask {
+  shared: Arc<WsShared>,
+  code: u16,
+  reason: Vec<u8>,
+}
+
+impl napi::Task for WsCloseTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let shared = &self.shared;
+    if shared.closed.load(Ordering::SeqCst) {
+      return Ok(());
+    }
+    shared.send_close(self.code, &self.reason)?;
+    // 等待对端回应 CLOSE 帧；有监听线程时由其接收
+    let deadline = Instant::now() + WS_CLOSE_TIMEOUT;
+    while !shared.closed.load(Ordering::SeqCst) && Instant::now() < deadline {
+      if shared.listening.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(WS_POLL_MS as u64));
+      } else if shared.recv_message()?.is_none() {
+        break;
+      }
+    }
+    shared.closed.store(true, Ordering::SeqCst);
+    Ok(())
+  }
+
+  fn resolve(&mut self, _env: napi::Env, _output: Self::Output) -> Result<Self::JsValue> {
+    Ok(())
+  }
+
+  fn reject(&mut self, _env: napi::Env, err: Error) -> Result<Self::JsValue> {
+    Err(err)
+  }
+}
+
+#[napi]
+pub struct CurlWebSocket {
+  // 持有 Curl 的引用，保证底层 easy handle 在连接期间不被回收
+  curl: Reference<Curl>,
+  shared: Arc<WsShared>,
+}
+
+#[napi]
+impl CurlWebSocket {
+  /// 使用已配置好（URL、impersonate 等）的 Curl 创建 WebSocket
+  #[napi(constructor)]
+  pub fn new(curl: Reference<Curl>) -> Result<Self> {
+    curl.check_close()?;
+    let lib = curl.lib();
+    lib.ws_send.get()?;
+    lib.ws_recv.get()?;
+    let shared = Arc::new(WsShared {
+      handle: curl.get_handle(),
+      lib,
+      io: Mutex::new(false),
+      close_sent: AtomicBool::new(false),
+      closed: AtomicBool::new(false),
+      listening: AtomicBool::new(false),
+      partial: Mutex::new(RawMessage::empty()),
+    });
+    curl.set_websocket(shared.clone());
+    Ok(CurlWebSocket { curl, shared })
+  }
+
+  /// 以 CONNECT_ONLY=2 完成 WebSocket 握手
+  #[napi]
+  pub fn connect(&self, env: Env) -> Result<AsyncTask<PerformTask>> {
+    log_info!("CurlWebSocket", handle = self.curl.id(); "connect");
+    self.curl.set_option(CurlOpt::ConnectOnly, Either5::B(2))?;
+    self.curl.perform_task(self.curl.clone(env)?)
+  }
+
+  /// 发送消息，默认字符串为 Text、Buffer 为 Binary
+  #[napi]
+  pub fn send(
+    &self,
+    data: Either<String, Buffer>,
+    flags: Option<u32>,
+  ) -> Result<AsyncTask<WsSendTask>> {
+    self.check_open()?;
+    let (data, default_flags) = match data {
+      Either::A(text) => (text.into_bytes(), CurlWsFlag::Text as u32),
+      Either::B(buffer) => (buffer.to_vec(), CurlWsFlag::Binary as u32),
+    };
+    Ok(AsyncTask::new(WsSendTask {
+      shared: self.shared.clone(),
+      data,
+      flags: flags.unwrap_or(default_flags),
+    }))
+  }
+
+  /// 发送 PING
+  #[napi]
+  pub fn ping(&self, payload: Option<Buffer>) -> Result<AsyncTask<WsSendTask>> {
+    self.check_open()?;
+    Ok(AsyncTask::new(WsSendTask {
+      shared: self.shared.clone(),
+      data: payload.map(|p| p.to_vec()).unwrap_or_default(),
+      flags: CurlWsFlag::Ping as u32,
+    }))
+  }
+
+  /// 接收下一条完整消息，连接关闭后返回 null
+  #[napi]
+  pub fn recv(&self) -> Result<AsyncTask<WsRecvTask>> {
+    if self.shared.listening.load(Ordering::SeqCst) {
+      return Err(Error::from_reason("recv cannot be used together with onMessage"));
+    }
+    Ok(AsyncTask::new(WsRecvTask {
+      shared: self.shared.clone(),
+    }))
+  }
+
+  /// 在后台线程持续接收消息并回调，连接关闭时以 null 结束
+  #[napi]
+  pub fn on_message(&self, callback: Arc<ThreadsafeFunction<Option<WsMessage>>>) -> Result<()> {
+    self.check_open()?;
+    if self.shared.listening.swap(true, Ordering::SeqCst) {
+      return Err(Error::from_reason("onMessage is already registered"));
+    }
+    let shared = self.shared.clone();
+    std::thread::spawn(move || {
+      loop {
+        match shared.recv_message() {
+          Ok(Some(message)) => {
+            callback.call(
+              Ok(Some(message.into_message())),
+              ThreadsafeFunctionCallMode::Blocking,
+            );
+          }
+          Ok(None) => {
+            callback.call(Ok(None), ThreadsafeFunctionCallMode::Blocking);
+            break;
+          }
+          Err(err) => {
+            callback.call(Err(err), ThreadsafeFunctionCallMode::Blocking);
+            break;
+          }
+        }
+      }
+      shared.listening.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+  }
+
+  /// 发起关闭握手，等待对端回应 CLOSE 帧
+  #[napi]
+  pub fn close(&self, code: Option<u32>, reason: Option<String>) -> AsyncTask<WsCloseTask> {
+    AsyncTask::new(WsCloseTask {
+      shared: self.shared.clone(),
+      code: code.unwrap_or(1000) as u16,
+      reason: reason.unwrap_or_default().into_bytes(),
+    })
+  }
+
+  #[napi(getter)]
+  pub fn closed(&self) -> bool {
+    self.shared.closed.load(Ordering::SeqCst)
+  }
+
+  fn check_open(&self) -> Result<()> {
+    self.curl.check_close()?;
+    if self.shared.closed.load(Ordering::SeqCst) || self.shared.close_sent.load(Ordering::SeqCst) {
+      return Err(Error::from_reason("WebSocket is closed"));
+    }
+    Ok(())
+  }
+}